@@ -1,13 +1,8 @@
 //! Example that runs on Linux using a serial-USB-adapter.
-use std::{
-    env, io,
-    net::{SocketAddr as StdSocketAddr, ToSocketAddrs},
-    thread,
-    time::Duration,
-};
+use std::{env, io, thread, time::Duration};
 
 use atat::bbqueue::BBBuffer;
-use embedded_nal::{SocketAddr as NalSocketAddr, TcpClientStack};
+use embedded_nal::{AddrType, Dns, SocketAddr, TcpClientStack};
 use esp_at_nal::{
     urc::URCMessages,
     wifi::{Adapter, WifiAdapter},
@@ -121,15 +116,9 @@ fn main() {
 
     // Resolve IPv4 for ifconfig.net
     let remote_host = "ifconfig.net";
-    let ipv4_and_port = (remote_host, 80)
-        .to_socket_addrs()
-        .unwrap()
-        .find_map(|addr| match addr {
-            StdSocketAddr::V4(v4) => Some((v4.ip().octets(), v4.port())),
-            _ => None,
-        })
-        .unwrap();
-    let socket_addr = NalSocketAddr::from(ipv4_and_port);
+    let ipv4 = nb::block!(adapter.get_host_by_name(remote_host, AddrType::IPv4))
+        .unwrap_or_else(|_| panic!("Failed to resolve {}", remote_host));
+    let socket_addr = SocketAddr::new(ipv4, 80);
 
     // Create TCP connection
     let mut socket = adapter.socket().expect("Failed to create socket");
@@ -142,7 +131,7 @@ fn main() {
     // Send HTTP request
     println!("Sending HTTP request...");
     let request = b"GET / HTTP/1.1\r\nAccept: text/plain\r\nHost: ifconfig.net\r\n\r\n";
-    adapter.send(&mut socket, request).expect("Could not send HTTP request");
+    nb::block!(adapter.send(&mut socket, request)).expect("Could not send HTTP request");
 
     // Read response
     let mut rx_buf = [0; RX_SIZE];
@@ -188,7 +177,10 @@ mod timer {
     use fugit::Instant;
 
     /// A timer with millisecond precision.
+    ///
+    /// `now()` is free-running since creation, so it is not affected by (re)starting the timer.
     pub struct SysTimer {
+        origin: StdInstant,
         start: StdInstant,
         duration_ms: u32,
         started: bool,
@@ -197,6 +189,7 @@ mod timer {
     impl SysTimer {
         pub fn new() -> SysTimer {
             SysTimer {
+                origin: StdInstant::now(),
                 start: StdInstant::now(),
                 duration_ms: 0,
                 started: false,
@@ -209,7 +202,7 @@ mod timer {
 
         /// Return current time `Instant`
         fn now(&mut self) -> fugit::TimerInstantU32<1000> {
-            let milliseconds = (StdInstant::now() - self.start).as_millis();
+            let milliseconds = (StdInstant::now() - self.origin).as_millis();
             let ticks: u32 = milliseconds.try_into().expect("u32 timer overflow");
             Instant::<u32, 1, 1000>::from_ticks(ticks)
         }
@@ -270,5 +263,17 @@ mod timer {
             assert!(duration_ms >= 500);
             assert!(duration_ms < 1000);
         }
+
+        #[test]
+        fn test_now_not_reset_by_start() {
+            let mut timer = SysTimer::new();
+
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            let before = timer.now();
+            timer.start(fugit::Duration::<u32, 1, 1000>::from_ticks(500)).unwrap();
+
+            assert!(timer.now() >= before);
+            assert!(timer.now().ticks() >= 20);
+        }
     }
 }
@@ -0,0 +1,103 @@
+//! # Active receiving mode
+//!
+//! By default ESP-AT is configured for passive receiving mode. Received data is buffered by ESP-AT and
+//! requested by an additional command (CIPRECVDATA) on each `receive()` call.
+//!
+//! In active receiving mode ESP-AT forwards received data instantly by +IPD messages. The data is buffered
+//! internally per socket (max. RX_SIZE bytes each), so `receive()` just copies the buffered data. This avoids the
+//! round trip of the additional command for latency-sensitive traffic.
+//!
+//! The active mode is enabled by `enable_active_receiving_mode()` of [Adapter] before the first connection is
+//! established. Please note the following limitations:
+//! * Each +IPD message needs to fit into the generic constant RX_SIZE and the ATAT ingress buffer. Otherwise
+//!   the message is discarded.
+//! * If data is not received in time and the socket buffer is full, further data is dropped and
+//!   [Error::BufferOverflow] is returned once.
+//! * UDP sockets are not supported, as datagram boundaries are not preserved by the socket buffers.
+//!
+//! Requires the `active-mode` feature.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::{SocketAddr, TcpClientStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//! adapter.enable_active_receiving_mode();
+//!
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.connect(&mut socket, SocketAddr::from_str("10.0.0.1:8080").unwrap()).unwrap();
+//!
+//! let mut buffer = [0x0; 64];
+//! let length = adapter.receive(&mut socket, &mut buffer).unwrap();
+//! assert_eq!(b"welcome!", &buffer[..length]);
+//! ````
+use crate::stack::Error;
+use crate::wifi::Adapter;
+use atat::AtatClient;
+use fugit_timer::Timer;
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Enables the active receiving mode. Should be called before the first connection is established.
+    /// If the passive mode was already configured, the receiving mode is changed on the next connection.
+    pub fn enable_active_receiving_mode(&mut self) {
+        if !self.active_mode {
+            self.active_mode = true;
+            self.receiving_mode_configured = false;
+        }
+    }
+
+    /// Appends received data to the buffer of the given link. Data exceeding the buffer capacity is dropped.
+    pub(crate) fn buffer_data(&mut self, link_id: usize, data: &[u8]) {
+        for byte in data {
+            if self.buffers[link_id].push_back(*byte).is_err() {
+                self.buffer_overflow[link_id] = true;
+                return;
+            }
+        }
+    }
+
+    /// Copies buffered data of the given link to the given buffer
+    pub(crate) fn receive_buffered(&mut self, link_id: usize, buffer: &mut [u8]) -> nb::Result<usize, Error> {
+        if core::mem::take(&mut self.buffer_overflow[link_id]) {
+            return nb::Result::Err(nb::Error::Other(Error::BufferOverflow));
+        }
+
+        if self.buffers[link_id].is_empty() {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        let mut length = 0;
+        while length < buffer.len() {
+            match self.buffers[link_id].pop_front() {
+                None => break,
+                Some(byte) => buffer[length] = byte,
+            }
+            length += 1;
+        }
+
+        nb::Result::Ok(length)
+    }
+
+    /// Asserts that active receiving mode is not enabled, as it's not supported by UDP sockets
+    pub(crate) fn assert_passive_receiving_mode(&self) -> Result<(), Error> {
+        if self.active_mode {
+            return Err(Error::ActiveModeUnsupported);
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,20 @@
 use core::fmt::Write;
 
-use crate::responses::LocalAddressResponse;
+use crate::dns::DnsError;
+use crate::firmware::{FirmwareError, FirmwareInfo};
+use crate::reset::ResetError;
 use crate::responses::NoResponse;
+use crate::responses::{
+    AccessPointResponse, ConnectionStateResponse, LocalAddressResponse, ReceiveLengthsResponse, ResolveDomainResponse,
+    StationResponse, MAX_LINKS,
+};
+use crate::soft_ap::SoftApError;
 use crate::stack::Error as StackError;
-use crate::wifi::{AddressErrors, JoinError};
+use crate::wifi::{AddressErrors, DnsServers, IpConfig, IpConfigError, JoinError, LeaveError, ScanError};
 use atat::atat_derive::AtatCmd;
 use atat::heapless::{String, Vec};
 use atat::{AtatCmd, Error as AtError, InternalError};
-use embedded_nal::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use embedded_nal::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use numtoa::NumToA;
 
 const MAX_IP_LENGTH: usize = 39; // IPv4: 15, IPv6: 39
@@ -19,6 +26,12 @@ pub trait CommandErrorHandler {
     /// Maps an unexpected WouldBlock error
     const WOULD_BLOCK_ERROR: Self::Error;
 
+    /// Returned if passthrough mode is active, as ESP-AT does not process commands then
+    const PASSTHROUGH_ERROR: Self::Error;
+
+    /// Returned if a socket transmission is still unconfirmed, as ESP-AT would interleave the responses
+    const SEND_PENDING_ERROR: Self::Error;
+
     /// Maps regular errors
     fn command_error(&self, error: AtError) -> Self::Error;
 }
@@ -36,226 +49,1545 @@ pub struct WifiModeCommand {
     mode: usize,
 }
 
-impl WifiModeCommand {
-    pub fn station_mode() -> Self {
+impl WifiModeCommand {
+    pub fn null_mode() -> Self {
+        Self { mode: 0 }
+    }
+
+    pub fn station_mode() -> Self {
+        Self { mode: 1 }
+    }
+
+    pub fn soft_ap_mode() -> Self {
+        Self { mode: 2 }
+    }
+
+    pub fn station_soft_ap_mode() -> Self {
+        Self { mode: 3 }
+    }
+}
+
+impl CommandErrorHandler for WifiModeCommand {
+    type Error = JoinError;
+    const WOULD_BLOCK_ERROR: Self::Error = JoinError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = JoinError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = JoinError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        JoinError::ModeError(error)
+    }
+}
+
+/// Command for setting the target WIFI access point parameters
+#[derive(Clone, Default, AtatCmd)]
+#[at_cmd("+CWJAP", NoResponse, timeout_ms = 20_000)]
+pub struct AccessPointConnectCommand {
+    /// The SSID of the target access point
+    #[at_arg(position = 0)]
+    ssid: String<32>,
+
+    /// The password/key of the target access point
+    #[at_arg(position = 0)]
+    password: String<64>,
+}
+
+impl AccessPointConnectCommand {
+    pub fn new(ssid: String<32>, password: String<64>) -> Self {
+        Self { ssid, password }
+    }
+}
+
+impl CommandErrorHandler for AccessPointConnectCommand {
+    type Error = JoinError;
+
+    const WOULD_BLOCK_ERROR: Self::Error = JoinError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = JoinError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = JoinError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        JoinError::ConnectError(error)
+    }
+}
+
+/// Sends the wrapped command without waiting for the response.
+/// Used for long running commands, whose result is signaled by URC messages.
+#[derive(Clone)]
+pub struct UnconfirmedCommand<Cmd> {
+    command: Cmd,
+}
+
+impl<Cmd> UnconfirmedCommand<Cmd> {
+    pub fn new(command: Cmd) -> Self {
+        Self { command }
+    }
+}
+
+impl<Cmd: AtatCmd<LEN>, const LEN: usize> AtatCmd<LEN> for UnconfirmedCommand<Cmd> {
+    type Response = NoResponse;
+
+    const MAX_TIMEOUT_MS: u32 = Cmd::MAX_TIMEOUT_MS;
+    const EXPECTS_RESPONSE_CODE: bool = false;
+
+    fn as_bytes(&self) -> Vec<u8, LEN> {
+        self.command.as_bytes()
+    }
+
+    fn parse(&self, _resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        Ok(NoResponse {})
+    }
+}
+
+impl<Cmd: CommandErrorHandler> CommandErrorHandler for UnconfirmedCommand<Cmd> {
+    type Error = Cmd::Error;
+    const WOULD_BLOCK_ERROR: Self::Error = Cmd::WOULD_BLOCK_ERROR;
+    const PASSTHROUGH_ERROR: Self::Error = Cmd::PASSTHROUGH_ERROR;
+    const SEND_PENDING_ERROR: Self::Error = Cmd::SEND_PENDING_ERROR;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        self.command.command_error(error)
+    }
+}
+
+/// Restarts the modem
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+RST", NoResponse, timeout_ms = 1_000)]
+pub struct RestartCommand {}
+
+impl RestartCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CommandErrorHandler for RestartCommand {
+    type Error = ResetError;
+    const WOULD_BLOCK_ERROR: Self::Error = ResetError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = ResetError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = ResetError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        ResetError::CommandError(error)
+    }
+}
+
+/// Disconnects from the current access point
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWQAP", NoResponse, timeout_ms = 5_000)]
+pub struct AccessPointDisconnectCommand {}
+
+impl AccessPointDisconnectCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CommandErrorHandler for AccessPointDisconnectCommand {
+    type Error = LeaveError;
+    const WOULD_BLOCK_ERROR: Self::Error = LeaveError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = LeaveError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = LeaveError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        LeaveError::CommandError(error)
+    }
+}
+
+/// Configures sorting and fields of the CWLAP command
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWLAPOPT", NoResponse, timeout_ms = 1_000)]
+pub struct AccessPointListOptionsCommand {
+    /// 0: unsorted, 1: sorted by RSSI
+    sort_enable: usize,
+
+    /// Bit mask of the fields included in CWLAP responses
+    ///     bit 0: encryption type
+    ///     bit 1: SSID
+    ///     bit 2: RSSI
+    ///     bit 3: MAC address
+    ///     bit 4: channel
+    print_mask: usize,
+}
+
+impl AccessPointListOptionsCommand {
+    /// Sorts by RSSI and includes the fields of [AccessPointResponse]
+    pub fn new() -> Self {
+        Self {
+            sort_enable: 1,
+            print_mask: 0b11111,
+        }
+    }
+}
+
+impl CommandErrorHandler for AccessPointListOptionsCommand {
+    type Error = ScanError;
+    const WOULD_BLOCK_ERROR: Self::Error = ScanError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = ScanError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = ScanError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        ScanError::OptionsError(error)
+    }
+}
+
+/// Lists the available access points
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWLAP", Vec<AccessPointResponse, 16>, timeout_ms = 10_000)]
+pub struct ListAccessPointsCommand {}
+
+impl ListAccessPointsCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CommandErrorHandler for ListAccessPointsCommand {
+    type Error = ScanError;
+    const WOULD_BLOCK_ERROR: Self::Error = ScanError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = ScanError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = ScanError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        ScanError::CommandError(error)
+    }
+}
+
+/// Configures the SoftAP
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWSAP", NoResponse, timeout_ms = 5_000)]
+pub struct SoftApConfigurationCommand {
+    /// SSID of the SoftAP
+    ssid: String<32>,
+
+    /// Password of the SoftAP, ignored for open networks
+    password: String<64>,
+
+    /// WIFI channel
+    channel: u8,
+
+    /// Encryption type
+    ///     0: Open
+    ///     2: WPA PSK
+    ///     3: WPA2 PSK
+    ///     4: WPA/WPA2 PSK
+    ecn: usize,
+
+    /// Max. number of connected stations (1-10)
+    max_connections: usize,
+
+    /// 0: SSID is broadcasted, 1: SSID is hidden
+    ssid_hidden: usize,
+}
+
+impl SoftApConfigurationCommand {
+    pub fn new(
+        ssid: String<32>,
+        password: String<64>,
+        channel: u8,
+        ecn: usize,
+        max_connections: usize,
+        hidden: bool,
+    ) -> Self {
+        Self {
+            ssid,
+            password,
+            channel,
+            ecn,
+            max_connections,
+            ssid_hidden: hidden as usize,
+        }
+    }
+}
+
+impl CommandErrorHandler for SoftApConfigurationCommand {
+    type Error = SoftApError;
+    const WOULD_BLOCK_ERROR: Self::Error = SoftApError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = SoftApError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = SoftApError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SoftApError::ConfigurationError(error)
+    }
+}
+
+/// Lists the stations connected to the SoftAP
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWLIF", Vec<StationResponse, 10>, timeout_ms = 1_000)]
+pub struct ListStationsCommand {}
+
+impl ListStationsCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CommandErrorHandler for ListStationsCommand {
+    type Error = SoftApError;
+    const WOULD_BLOCK_ERROR: Self::Error = SoftApError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = SoftApError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = SoftApError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        SoftApError::StationListError(error)
+    }
+}
+
+/// Command for receiving local address information including IP and MAC
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIFSR", Vec<LocalAddressResponse, 4>, timeout_ms = 5_000)]
+pub struct ObtainLocalAddressCommand {}
+
+impl ObtainLocalAddressCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CommandErrorHandler for ObtainLocalAddressCommand {
+    type Error = AddressErrors;
+    const WOULD_BLOCK_ERROR: Self::Error = AddressErrors::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = AddressErrors::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = AddressErrors::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        AddressErrors::CommandError(error)
+    }
+}
+
+/// Resolves the IP address of the given domain name
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPDOMAIN", ResolveDomainResponse, timeout_ms = 10_000)]
+pub struct ResolveDomainCommand {
+    /// Domain name
+    domain: String<64>,
+
+    /// 1: Resolve IPv4 address first, 2: resolve only IPv4 address, 3: resolve only IPv6 address
+    ip_network: usize,
+}
+
+impl ResolveDomainCommand {
+    /// Resolves the IPv4 address or the IPv6 address if the domain has no IPv4 address
+    pub fn either(domain: String<64>) -> Self {
+        Self { domain, ip_network: 1 }
+    }
+
+    /// Resolves just the IPv4 address
+    pub fn ipv4(domain: String<64>) -> Self {
+        Self { domain, ip_network: 2 }
+    }
+
+    /// Resolves just the IPv6 address
+    pub fn ipv6(domain: String<64>) -> Self {
+        Self { domain, ip_network: 3 }
+    }
+}
+
+impl CommandErrorHandler for ResolveDomainCommand {
+    type Error = DnsError;
+    const WOULD_BLOCK_ERROR: Self::Error = DnsError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = DnsError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = DnsError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        DnsError::ResolveError(error)
+    }
+}
+
+/// Sets a static IPv4 address of the station interface. DHCP gets disabled by ESP-AT.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTA", NoResponse, timeout_ms = 1_000)]
+pub struct SetStaticIpCommand {
+    /// IPv4 address
+    ip: String<MAX_IP_LENGTH>,
+
+    /// Gateway address
+    gateway: String<MAX_IP_LENGTH>,
+
+    /// Netmask
+    netmask: String<MAX_IP_LENGTH>,
+}
+
+impl SetStaticIpCommand {
+    pub fn new(config: &IpConfig) -> Self {
+        Self {
+            ip: ipv4_to_string(&config.ip),
+            gateway: ipv4_to_string(&config.gateway),
+            netmask: ipv4_to_string(&config.netmask),
+        }
+    }
+}
+
+impl CommandErrorHandler for SetStaticIpCommand {
+    type Error = IpConfigError;
+    const WOULD_BLOCK_ERROR: Self::Error = IpConfigError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = IpConfigError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = IpConfigError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        IpConfigError::ConfigurationError(error)
+    }
+}
+
+/// Queries the version information of the firmware
+#[derive(Clone)]
+pub struct FirmwareVersionCommand {}
+
+impl FirmwareVersionCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AtatCmd<8> for FirmwareVersionCommand {
+    type Response = FirmwareInfo;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 8> {
+        Vec::from_slice(b"AT+GMR\r\n").unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        FirmwareInfo::from_response(resp.map_err(|_| AtError::Error)?)
+    }
+}
+
+impl CommandErrorHandler for FirmwareVersionCommand {
+    type Error = FirmwareError;
+    const WOULD_BLOCK_ERROR: Self::Error = FirmwareError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = FirmwareError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = FirmwareError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        FirmwareError::CommandError(error)
+    }
+}
+
+/// Queries the IPv4 address, gateway and netmask of the station interface
+#[derive(Clone)]
+pub struct IpConfigQueryCommand {}
+
+impl IpConfigQueryCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AtatCmd<12> for IpConfigQueryCommand {
+    type Response = IpConfig;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 12> {
+        Vec::from_slice(b"AT+CIPSTA?\r\n").unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        IpConfig::from_response(resp.map_err(|_| AtError::Error)?)
+    }
+}
+
+impl CommandErrorHandler for IpConfigQueryCommand {
+    type Error = IpConfigError;
+    const WOULD_BLOCK_ERROR: Self::Error = IpConfigError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = IpConfigError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = IpConfigError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        IpConfigError::ConfigurationError(error)
+    }
+}
+
+/// Enables/Disables DHCP of the station interface
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CWDHCP", NoResponse, timeout_ms = 1_000)]
+pub struct SetDhcpCommand {
+    /// 0: disable, 1: enable
+    operate: usize,
+
+    /// Bit mask of the affected interfaces
+    ///     bit 0: station
+    ///     bit 1: SoftAP
+    mode: usize,
+}
+
+impl SetDhcpCommand {
+    /// Enables or disables DHCP of the station interface
+    pub fn station(enabled: bool) -> Self {
+        Self {
+            operate: enabled as usize,
+            mode: 1,
+        }
+    }
+}
+
+impl CommandErrorHandler for SetDhcpCommand {
+    type Error = IpConfigError;
+    const WOULD_BLOCK_ERROR: Self::Error = IpConfigError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = IpConfigError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = IpConfigError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        IpConfigError::DhcpError(error)
+    }
+}
+
+/// Sets custom DNS servers or resets to the servers provided by DHCP
+#[derive(Clone)]
+pub struct SetDnsServersCommand {
+    /// Custom DNS server addresses. If empty, servers of DHCP are used.
+    servers: Vec<String<MAX_IP_LENGTH>, 3>,
+
+    /// True if CIPDNS_CUR command is used, which does not store the configuration in flash
+    temporary: bool,
+}
+
+impl SetDnsServersCommand {
+    /// Sets the given custom DNS servers (max. 3)
+    pub fn custom(servers: &[IpAddr]) -> Self {
+        let mut command = Self::dhcp();
+
+        for server in servers.iter().take(3) {
+            let address = match server {
+                IpAddr::V4(address) => ipv4_to_string(address),
+                IpAddr::V6(address) => ipv6_to_string(address),
+            };
+            command.servers.push(address).unwrap();
+        }
+
+        command
+    }
+
+    /// Resets to the DNS servers provided by DHCP
+    pub fn dhcp() -> Self {
+        Self {
+            servers: Vec::new(),
+            temporary: false,
+        }
+    }
+
+    /// Uses CIPDNS_CUR command for not storing the configuration in flash (ESP-AT v1.x)
+    pub fn temporary(mut self, temporary: bool) -> Self {
+        self.temporary = temporary;
+        self
+    }
+}
+
+impl AtatCmd<144> for SetDnsServersCommand {
+    type Response = NoResponse;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 144> {
+        let mut command: Vec<u8, 144> = Vec::new();
+
+        if self.temporary {
+            command.extend_from_slice(b"AT+CIPDNS_CUR=").unwrap();
+        } else {
+            command.extend_from_slice(b"AT+CIPDNS=").unwrap();
+        }
+
+        if self.servers.is_empty() {
+            command.extend_from_slice(b"0\r\n").unwrap();
+            return command;
+        }
+
+        command.push(b'1').unwrap();
+        for server in &self.servers {
+            command.extend_from_slice(b",\"").unwrap();
+            command.extend_from_slice(server.as_bytes()).unwrap();
+            command.push(b'"').unwrap();
+        }
+        command.extend_from_slice(b"\r\n").unwrap();
+
+        command
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        resp.map_err(|_| AtError::Error)?;
+        Ok(NoResponse {})
+    }
+}
+
+impl CommandErrorHandler for SetDnsServersCommand {
+    type Error = DnsError;
+    const WOULD_BLOCK_ERROR: Self::Error = DnsError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = DnsError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = DnsError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        DnsError::ConfigurationError(error)
+    }
+}
+
+/// Queries the active DNS servers
+#[derive(Clone)]
+pub struct DnsServersQueryCommand {
+    /// True if CIPDNS_CUR command is used
+    temporary: bool,
+}
+
+impl DnsServersQueryCommand {
+    pub fn new() -> Self {
+        Self { temporary: false }
+    }
+
+    /// Uses CIPDNS_CUR command (ESP-AT v1.x)
+    pub fn temporary(mut self, temporary: bool) -> Self {
+        self.temporary = temporary;
+        self
+    }
+}
+
+impl AtatCmd<16> for DnsServersQueryCommand {
+    type Response = DnsServers;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 16> {
+        if self.temporary {
+            return Vec::from_slice(b"AT+CIPDNS_CUR?\r\n").unwrap();
+        }
+
+        Vec::from_slice(b"AT+CIPDNS?\r\n").unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        let response = resp.map_err(|_| AtError::Error)?;
+
+        if self.temporary {
+            return DnsServers::from_legacy_response(response);
+        }
+
+        DnsServers::from_response(response)
+    }
+}
+
+impl CommandErrorHandler for DnsServersQueryCommand {
+    type Error = DnsError;
+    const WOULD_BLOCK_ERROR: Self::Error = DnsError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = DnsError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = DnsError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        DnsError::ConfigurationError(error)
+    }
+}
+
+/// Enables/Disables multiple connections
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPMUX", NoResponse, timeout_ms = 1_000)]
+pub struct SetMultipleConnectionsCommand {
+    /// 0: single connection, 1: multiple connections
+    mode: usize,
+}
+
+impl SetMultipleConnectionsCommand {
+    /// Enables multiple connections
+    pub fn multiple() -> Self {
+        Self { mode: 1 }
+    }
+
+    /// Enables single connection mode, required for passthrough mode
+    pub fn single() -> Self {
+        Self { mode: 0 }
+    }
+}
+
+impl CommandErrorHandler for SetMultipleConnectionsCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        match self.mode {
+            0 => StackError::EnablingSingleConnectionFailed(error),
+            _ => StackError::EnablingMultiConnectionsFailed(error),
+        }
+    }
+}
+
+/// Sets the transmission mode
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPMODE", NoResponse, timeout_ms = 1_000)]
+pub struct TransmissionModeCommand {
+    /// 0: normal transmission mode, 1: passthrough (transparent transmission) mode
+    mode: usize,
+}
+
+impl TransmissionModeCommand {
+    /// Normal transmission mode
+    pub fn normal() -> Self {
+        Self { mode: 0 }
+    }
+
+    /// Passthrough (transparent transmission) mode
+    pub fn passthrough() -> Self {
+        Self { mode: 1 }
+    }
+}
+
+impl CommandErrorHandler for TransmissionModeCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TransmissionModeError(error)
+    }
+}
+
+/// Sets the socket receiving mode
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPRECVMODE", NoResponse, timeout_ms = 1_000)]
+pub struct SetSocketReceivingModeCommand {
+    /// 0: active mode => ESP-AT will send all the received socket data instantly to the host MCU
+    /// 1: passive mode => ESP-AT will keep the received socket data in an internal buffer
+    mode: usize,
+}
+
+impl SetSocketReceivingModeCommand {
+    /// Enables the passive receiving mode
+    pub fn passive_mode() -> Self {
+        Self { mode: 1 }
+    }
+
+    /// Enables the active receiving mode
+    #[cfg(feature = "active-mode")]
+    pub fn active_mode() -> Self {
+        Self { mode: 0 }
+    }
+}
+
+impl CommandErrorHandler for SetSocketReceivingModeCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        match self.mode {
+            #[cfg(feature = "active-mode")]
+            0 => StackError::EnablingActiveSocketModeFailed(error),
+            _ => StackError::EnablingPassiveSocketModeFailed(error),
+        }
+    }
+}
+
+/// Establish TCP Connection, UDP Transmission, or SSL Connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTART", NoResponse, timeout_ms = 5_000)]
+pub struct ConnectCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Connection type, e.g. TCP, TCPv6, SSL, etc.
+    connection_type: String<5>,
+
+    /// Remote IPv4 or IPV6 address
+    remote_host: String<MAX_IP_LENGTH>,
+
+    /// Remote port
+    port: u16,
+
+    /// Interval of TCP keep-alive probes in seconds, omitted if None
+    keep_alive: Option<u16>,
+}
+
+/// Establishes the TCP connection of the single connection mode
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTART", NoResponse, timeout_ms = 5_000)]
+pub struct SingleConnectCommand {
+    /// Connection type, e.g. TCP or TCPv6
+    connection_type: String<5>,
+
+    /// Remote IPv4 or IPV6 address
+    remote_host: String<MAX_IP_LENGTH>,
+
+    /// Remote port
+    port: u16,
+}
+
+impl SingleConnectCommand {
+    /// Establishes a IPv4 TCP connection
+    pub fn tcp_v4(remote: SocketAddrV4) -> Self {
+        Self {
+            connection_type: String::from("TCP"),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+        }
+    }
+
+    /// Establishes a IPv6 TCP connection
+    pub fn tcp_v6(remote: SocketAddrV6) -> Self {
+        Self {
+            connection_type: String::from("TCPv6"),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+        }
+    }
+}
+
+impl CommandErrorHandler for SingleConnectCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ConnectError(error)
+    }
+}
+
+/// Convert a `IPv4Addr` to a heapless `String`
+fn ipv4_to_string(ip: &Ipv4Addr) -> String<MAX_IP_LENGTH> {
+    let mut ip_string = String::new();
+    let mut num_buf = [0u8; 3];
+    for (i, octet) in ip.octets().iter().enumerate() {
+        ip_string.write_str(octet.numtoa_str(10, &mut num_buf)).unwrap();
+        if i != 3 {
+            ip_string.write_char('.').unwrap();
+        }
+    }
+    ip_string
+}
+
+/// Convert a `SocketAddrV6` IP to a heapless `String`
+fn ipv6_to_string(ip: &Ipv6Addr) -> String<MAX_IP_LENGTH> {
+    let mut ip_string = String::new();
+    let mut hex_buf = [0u8; 4];
+    for (i, segment) in ip.segments().iter().enumerate() {
+        // Write segment (hexadectet)
+        if segment == &0 {
+            // All-zero-segments can be shortened
+            ip_string.write_str("0").unwrap()
+        } else {
+            // Hex-encode IPv6 segment
+            base16::encode_config_slice(&segment.to_be_bytes(), base16::EncodeLower, &mut hex_buf);
+            ip_string
+                // Safety: The result from hex-encoding will always be valid UTF-8
+                .write_str(unsafe { core::str::from_utf8_unchecked(&hex_buf) })
+                .unwrap();
+        }
+
+        // Write separator
+        if i != 7 {
+            ip_string.write_char(':').unwrap();
+        }
+    }
+    ip_string
+}
+
+impl ConnectCommand {
+    /// Establishes a IPv4 TCP connection
+    pub fn tcp_v4(link_id: usize, remote: SocketAddrV4) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("TCP"),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+            keep_alive: None,
+        }
+    }
+
+    /// Establishes a IPv6 TCP connection
+    pub fn tcp_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("TCPv6"),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+            keep_alive: None,
+        }
+    }
+
+    /// Establishes a IPv4 SSL connection
+    pub fn ssl_v4(link_id: usize, remote: SocketAddrV4) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("SSL"),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+            keep_alive: None,
+        }
+    }
+
+    /// Establishes a IPv6 SSL connection
+    pub fn ssl_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("SSLv6"),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+            keep_alive: None,
+        }
+    }
+
+    /// Establishes a IPv4 UDP transmission
+    pub fn udp_v4(link_id: usize, remote: SocketAddrV4) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("UDP"),
+            remote_host: ipv4_to_string(remote.ip()),
+            port: remote.port(),
+            keep_alive: None,
+        }
+    }
+
+    /// Establishes a IPv6 UDP transmission
+    pub fn udp_v6(link_id: usize, remote: SocketAddrV6) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("UDPv6"),
+            remote_host: ipv6_to_string(remote.ip()),
+            port: remote.port(),
+            keep_alive: None,
+        }
+    }
+
+    /// Enables TCP keep-alive probes with the given interval in seconds
+    pub fn with_keep_alive(mut self, keep_alive: Option<u16>) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+}
+
+impl CommandErrorHandler for ConnectCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ConnectError(error)
+    }
+}
+
+/// Sets the socket options of a TCP link
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPTCPOPT", NoResponse, timeout_ms = 1_000)]
+pub struct SocketOptionsCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Linger timeout in seconds, -1: disabled
+    so_linger: i32,
+
+    /// 1: Nagle's algorithm is disabled
+    tcp_nodelay: u8,
+
+    /// Send timeout in ms, 0: no timeout
+    so_sndtimeo: u32,
+}
+
+impl SocketOptionsCommand {
+    pub fn new(link_id: usize, so_linger: i32, tcp_nodelay: bool, so_sndtimeo: u32) -> Self {
+        Self {
+            link_id,
+            so_linger,
+            tcp_nodelay: tcp_nodelay as u8,
+            so_sndtimeo,
+        }
+    }
+}
+
+impl CommandErrorHandler for SocketOptionsCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::SocketOptionsError(error)
+    }
+}
+
+/// Sets the server name indication (SNI) of a SSL client connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSSLCSNI", NoResponse, timeout_ms = 1_000)]
+pub struct SslServerNameCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Server name
+    server_name: String<64>,
+}
+
+impl SslServerNameCommand {
+    pub fn new(link_id: usize, server_name: String<64>) -> Self {
+        Self { link_id, server_name }
+    }
+}
+
+impl CommandErrorHandler for SslServerNameCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsConfigurationError(error)
+    }
+}
+
+/// Sets the ALPN protocols (max. 3) of a SSL client connection
+#[derive(Clone)]
+pub struct SslAlpnCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Protocol names
+    protocols: Vec<String<32>, 3>,
+}
+
+impl SslAlpnCommand {
+    pub fn new(link_id: usize, protocols: Vec<String<32>, 3>) -> Self {
+        Self { link_id, protocols }
+    }
+}
+
+impl AtatCmd<128> for SslAlpnCommand {
+    type Response = NoResponse;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 128> {
+        let mut command: Vec<u8, 128> = Vec::new();
+        let mut num_buf = [0u8; 20];
+
+        command.extend_from_slice(b"AT+CIPSSLCALPN=").unwrap();
+        command.extend_from_slice(self.link_id.numtoa(10, &mut num_buf)).unwrap();
+        command.push(b',').unwrap();
+        command
+            .extend_from_slice(self.protocols.len().numtoa(10, &mut num_buf))
+            .unwrap();
+
+        for protocol in &self.protocols {
+            command.extend_from_slice(b",\"").unwrap();
+            command.extend_from_slice(protocol.as_bytes()).unwrap();
+            command.push(b'"').unwrap();
+        }
+        command.extend_from_slice(b"\r\n").unwrap();
+
+        command
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        resp.map_err(|_| AtError::Error)?;
+        Ok(NoResponse {})
+    }
+}
+
+impl CommandErrorHandler for SslAlpnCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsConfigurationError(error)
+    }
+}
+
+/// Sets the authentication mode of a SSL client connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSSLCCONF", NoResponse, timeout_ms = 1_000)]
+pub struct SslConfigurationCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// 0: no authentication
+    /// 1: client provides certificate for server verification
+    /// 2: client verifies server certificate
+    /// 3: mutual authentication
+    auth_mode: usize,
+
+    /// Index of the client certificate and private key
+    pki_number: usize,
+
+    /// Index of the CA certificate
+    ca_number: usize,
+}
+
+impl SslConfigurationCommand {
+    pub fn new(link_id: usize, auth_mode: usize, pki_number: usize, ca_number: usize) -> Self {
+        Self {
+            link_id,
+            auth_mode,
+            pki_number,
+            ca_number,
+        }
+    }
+}
+
+impl CommandErrorHandler for SslConfigurationCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsConfigurationError(error)
+    }
+}
+
+/// Sets the pre-shared key (PSK) of a SSL client connection
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSSLCPSK", NoResponse, timeout_ms = 1_000)]
+pub struct SslPskCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// PSK identity
+    psk: String<32>,
+
+    /// PSK hint
+    hint: String<32>,
+}
+
+impl SslPskCommand {
+    pub fn new(link_id: usize, psk: String<32>, hint: String<32>) -> Self {
+        Self { link_id, psk, hint }
+    }
+}
+
+impl CommandErrorHandler for SslPskCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TlsConfigurationError(error)
+    }
+}
+
+/// Initiates writing binary data to the manufacturing NVS partition (mfg_nvs)
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SYSMFG", NoResponse, timeout_ms = 5_000)]
+pub struct ManufacturingWriteCommand {
+    /// 1: read, 2: write
+    operation: usize,
+
+    /// Namespace, e.g. client_cert
+    namespace: String<16>,
+
+    /// Key within the namespace, e.g. client_cert.0
+    key: String<16>,
+
+    /// Data type, 8: binary
+    data_type: usize,
+
+    /// Length of the data to write
+    length: usize,
+}
+
+impl ManufacturingWriteCommand {
+    /// Writes binary data of the given length
+    pub fn binary(namespace: String<16>, key: String<16>, length: usize) -> Self {
+        Self {
+            operation: 2,
+            namespace,
+            key,
+            data_type: 8,
+            length,
+        }
+    }
+}
+
+impl CommandErrorHandler for ManufacturingWriteCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::CredentialWriteError(error)
+    }
+}
+
+/// Erases a flash partition
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SYSFLASH", NoResponse, timeout_ms = 5_000)]
+pub struct FlashEraseCommand {
+    /// 0: erase, 1: write
+    operation: usize,
+
+    /// Partition name, e.g. client_cert
+    partition: String<16>,
+}
+
+impl FlashEraseCommand {
+    pub fn new(partition: String<16>) -> Self {
+        Self {
+            operation: 0,
+            partition,
+        }
+    }
+}
+
+impl CommandErrorHandler for FlashEraseCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::CredentialWriteError(error)
+    }
+}
+
+/// Initiates writing data to a flash partition
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+SYSFLASH", NoResponse, timeout_ms = 5_000)]
+pub struct FlashWriteCommand {
+    /// 0: erase, 1: write
+    operation: usize,
+
+    /// Partition name, e.g. client_cert
+    partition: String<16>,
+
+    /// Offset within the partition
+    offset: usize,
+
+    /// Length of the data to write
+    length: usize,
+}
+
+impl FlashWriteCommand {
+    /// Writes data of the given length at the start of the partition
+    pub fn new(partition: String<16>, length: usize) -> Self {
+        Self {
+            operation: 1,
+            partition,
+            offset: 0,
+            length,
+        }
+    }
+}
+
+impl CommandErrorHandler for FlashWriteCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::CredentialWriteError(error)
+    }
+}
+
+/// Establishes a UDP transmission bound to a fixed local port
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSTART", NoResponse, timeout_ms = 5_000)]
+pub struct UdpBindCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Connection type, always UDP
+    connection_type: String<5>,
+
+    /// Remote IPv4 address
+    remote_host: String<MAX_IP_LENGTH>,
+
+    /// Remote port
+    remote_port: u16,
+
+    /// Local port
+    local_port: u16,
+
+    /// 0: remote is fixed
+    /// 1: remote changes once to the sender of the first received datagram
+    /// 2: remote changes to the sender of each received datagram
+    mode: usize,
+}
+
+impl UdpBindCommand {
+    /// Binds to the given local port, accepting datagrams of any remote
+    pub fn new(link_id: usize, local_port: u16) -> Self {
+        Self {
+            link_id,
+            connection_type: String::from("UDP"),
+            remote_host: String::from("0.0.0.0"),
+            remote_port: 0,
+            local_port,
+            mode: 2,
+        }
+    }
+}
+
+impl CommandErrorHandler for UdpBindCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::BindError(error)
+    }
+}
+
+/// Enables/Disables the remote information (IP + port) in +IPD messages
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPDINFO", NoResponse, timeout_ms = 1_000)]
+pub struct SetDataInfoCommand {
+    /// 0: no remote information, 1: remote IP and port are included
+    mode: usize,
+}
+
+impl SetDataInfoCommand {
+    /// Enables the remote information
+    pub fn enabled() -> Self {
         Self { mode: 1 }
     }
 }
 
-impl CommandErrorHandler for WifiModeCommand {
-    type Error = JoinError;
-    const WOULD_BLOCK_ERROR: Self::Error = JoinError::UnexpectedWouldBlock;
+impl CommandErrorHandler for SetDataInfoCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        JoinError::ModeError(error)
+        StackError::EnablingDataInfoFailed(error)
     }
 }
 
-/// Command for setting the target WIFI access point parameters
-#[derive(Clone, Default, AtatCmd)]
-#[at_cmd("+CWJAP", NoResponse, timeout_ms = 20_000)]
-pub struct AccessPointConnectCommand {
-    /// The SSID of the target access point
-    #[at_arg(position = 0)]
-    ssid: String<32>,
-
-    /// The password/key of the target access point
-    #[at_arg(position = 0)]
-    password: String<64>,
+/// Sets the maximum number of clients allowed to connect to the TCP server
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSERVERMAXCONN", NoResponse, timeout_ms = 1_000)]
+pub struct ServerMaxConnectionsCommand {
+    /// Maximum number of clients
+    count: usize,
 }
 
-impl AccessPointConnectCommand {
-    pub fn new(ssid: String<32>, password: String<64>) -> Self {
-        Self { ssid, password }
+impl ServerMaxConnectionsCommand {
+    pub fn new(count: usize) -> Self {
+        Self { count }
     }
 }
 
-impl CommandErrorHandler for AccessPointConnectCommand {
-    type Error = JoinError;
-
-    const WOULD_BLOCK_ERROR: Self::Error = JoinError::UnexpectedWouldBlock;
+impl CommandErrorHandler for ServerMaxConnectionsCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        JoinError::ConnectError(error)
+        StackError::ServerError(error)
     }
 }
 
-/// Command for receiving local address information including IP and MAC
+/// Creates or deletes a TCP server
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIFSR", Vec<LocalAddressResponse, 4>, timeout_ms = 5_000)]
-pub struct ObtainLocalAddressCommand {}
+#[at_cmd("+CIPSERVER", NoResponse, timeout_ms = 1_000)]
+pub struct ServerCommand {
+    /// 0: delete server, 1: create server
+    mode: usize,
 
-impl ObtainLocalAddressCommand {
-    pub fn new() -> Self {
-        Self {}
+    /// mode = 0 => 0: keep existing client connections, 1: close all client connections
+    /// mode = 1 => Local port
+    param: u16,
+}
+
+impl ServerCommand {
+    /// Creates a server listening on the given port
+    pub fn start(port: u16) -> Self {
+        Self { mode: 1, param: port }
+    }
+
+    /// Deletes the server, but keeps the existing client connections
+    pub fn stop() -> Self {
+        Self { mode: 0, param: 0 }
     }
 }
 
-impl CommandErrorHandler for ObtainLocalAddressCommand {
-    type Error = AddressErrors;
-    const WOULD_BLOCK_ERROR: Self::Error = AddressErrors::UnexpectedWouldBlock;
+impl CommandErrorHandler for ServerCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        AddressErrors::CommandError(error)
+        StackError::ServerError(error)
     }
 }
 
-/// Enables/Disables multiple connections
+/// Command for obtaining the state of all connections
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPMUX", NoResponse, timeout_ms = 1_000)]
-pub struct SetMultipleConnectionsCommand {
-    /// 0: single connection, 1: multiple connections
-    mode: usize,
-}
+#[at_cmd("+CIPSTATE?", Vec<ConnectionStateResponse, MAX_LINKS>, timeout_ms = 1_000)]
+pub struct ConnectionStateCommand {}
 
-impl SetMultipleConnectionsCommand {
-    /// Enables multiple connections
-    pub fn multiple() -> Self {
-        Self { mode: 1 }
+impl ConnectionStateCommand {
+    pub fn new() -> Self {
+        Self {}
     }
 }
 
-impl CommandErrorHandler for SetMultipleConnectionsCommand {
+impl CommandErrorHandler for ConnectionStateCommand {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::EnablingMultiConnectionsFailed(error)
+        StackError::ConnectionStateError(error)
     }
 }
 
-/// Sets the socket receiving mode
-#[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPRECVMODE", NoResponse, timeout_ms = 1_000)]
-pub struct SetSocketReceivingModeCommand {
-    /// 0: active mode => ESP-AT will send all the received socket data instantly to the host MCU
-    /// 1: passive mode => ESP-AT will keep the received socket data in an internal buffer
-    mode: usize,
+/// Command for obtaining the state of all connections on older firmware versions lacking CIPSTATE
+#[derive(Clone)]
+pub struct ConnectionStatusCommand {}
+
+impl ConnectionStatusCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
 }
 
-impl SetSocketReceivingModeCommand {
-    /// Enables the passive receiving mode
-    pub fn passive_mode() -> Self {
-        Self { mode: 1 }
+impl AtatCmd<14> for ConnectionStatusCommand {
+    type Response = Vec<ConnectionStateResponse, MAX_LINKS>;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 14> {
+        Vec::from_slice(b"AT+CIPSTATUS\r\n").unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        ConnectionStateResponse::from_status_response(resp.map_err(|_| AtError::Error)?)
     }
 }
 
-impl CommandErrorHandler for SetSocketReceivingModeCommand {
+impl CommandErrorHandler for ConnectionStatusCommand {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::EnablingPassiveSocketModeFailed(error)
+        StackError::ConnectionStateError(error)
     }
 }
 
-/// Establish TCP Connection, UDP Transmission, or SSL Connection
+/// Initiates the transmission of data
 #[derive(Clone, AtatCmd)]
-#[at_cmd("+CIPSTART", NoResponse, timeout_ms = 5_000)]
-pub struct ConnectCommand {
+#[at_cmd("+CIPSEND", NoResponse, timeout_ms = 1_000)]
+pub struct TransmissionPrepareCommand {
     /// Socket ID
     link_id: usize,
 
-    /// Connection type, e.g. TCP, TCPv6, SSL, etc.
-    connection_type: String<5>,
-
-    /// Remote IPv4 or IPV6 address
-    remote_host: String<MAX_IP_LENGTH>,
+    /// Length of the data to transmit
+    length: usize,
+}
 
-    /// Remote port
-    port: u16,
+impl TransmissionPrepareCommand {
+    pub fn new(link_id: usize, length: usize) -> Self {
+        Self { link_id, length }
+    }
 }
 
-/// Convert a `IPv4Addr` to a heapless `String`
-fn ipv4_to_string(ip: &Ipv4Addr) -> String<MAX_IP_LENGTH> {
-    let mut ip_string = String::new();
-    let mut num_buf = [0u8; 3];
-    for (i, octet) in ip.octets().iter().enumerate() {
-        ip_string.write_str(octet.numtoa_str(10, &mut num_buf)).unwrap();
-        if i != 3 {
-            ip_string.write_char('.').unwrap();
-        }
+impl CommandErrorHandler for TransmissionPrepareCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TransmissionStartFailed(error)
     }
-    ip_string
 }
 
-/// Convert a `SocketAddrV6` IP to a heapless `String`
-fn ipv6_to_string(ip: &Ipv6Addr) -> String<MAX_IP_LENGTH> {
-    let mut ip_string = String::new();
-    let mut hex_buf = [0u8; 4];
-    for (i, segment) in ip.segments().iter().enumerate() {
-        // Write segment (hexadectet)
-        if segment == &0 {
-            // All-zero-segments can be shortened
-            ip_string.write_str("0").unwrap()
-        } else {
-            // Hex-encode IPv6 segment
-            base16::encode_config_slice(&segment.to_be_bytes(), base16::EncodeLower, &mut hex_buf);
-            ip_string
-                // Safety: The result from hex-encoding will always be valid UTF-8
-                .write_str(unsafe { core::str::from_utf8_unchecked(&hex_buf) })
-                .unwrap();
-        }
+/// Starts the passthrough transmission. Any data sent afterwards is forwarded by ESP-AT without framing.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSEND", NoResponse, timeout_ms = 1_000)]
+pub struct PassthroughSendCommand {}
 
-        // Write separator
-        if i != 7 {
-            ip_string.write_char(':').unwrap();
-        }
+impl PassthroughSendCommand {
+    pub fn new() -> Self {
+        Self {}
     }
-    ip_string
 }
 
-impl ConnectCommand {
-    /// Establishes a IPv4 TCP connection
-    pub fn tcp_v4(link_id: usize, remote: SocketAddrV4) -> Self {
-        Self {
-            link_id,
-            connection_type: String::from("TCP"),
-            remote_host: ipv4_to_string(remote.ip()),
-            port: remote.port(),
-        }
+impl CommandErrorHandler for PassthroughSendCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::TransmissionStartFailed(error)
     }
+}
 
-    /// Establishes a IPv6 TCP connection
-    pub fn tcp_v6(link_id: usize, remote: SocketAddrV6) -> Self {
-        Self {
-            link_id,
-            connection_type: String::from("TCPv6"),
-            remote_host: ipv6_to_string(remote.ip()),
-            port: remote.port(),
-        }
+/// Initiates the transmission of long data (CIPSENDL). Data may be transmitted in multiple blocks
+/// afterwards, which are not confirmed individually.
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPSENDL", NoResponse, timeout_ms = 1_000)]
+pub struct LongTransmissionPrepareCommand {
+    /// Socket ID
+    link_id: usize,
+
+    /// Total length of the data to transmit
+    length: usize,
+}
+
+impl LongTransmissionPrepareCommand {
+    pub fn new(link_id: usize, length: usize) -> Self {
+        Self { link_id, length }
     }
 }
 
-impl CommandErrorHandler for ConnectCommand {
+impl CommandErrorHandler for LongTransmissionPrepareCommand {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
-        StackError::ConnectError(error)
+        StackError::TransmissionStartFailed(error)
     }
 }
 
-/// Initiates the transmission of data
+/// Initiates the transmission of a UDP datagram to the given remote
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+CIPSEND", NoResponse, timeout_ms = 1_000)]
-pub struct TransmissionPrepareCommand {
+pub struct RemoteTransmissionPrepareCommand {
     /// Socket ID
     link_id: usize,
 
     /// Length of the data to transmit
     length: usize,
+
+    /// Remote IPv4 or IPV6 address
+    remote_host: String<MAX_IP_LENGTH>,
+
+    /// Remote port
+    remote_port: u16,
 }
 
-impl TransmissionPrepareCommand {
-    pub fn new(link_id: usize, length: usize) -> Self {
-        Self { link_id, length }
+impl RemoteTransmissionPrepareCommand {
+    pub fn new(link_id: usize, length: usize, remote: SocketAddr) -> Self {
+        let remote_host = match remote {
+            SocketAddr::V4(address) => ipv4_to_string(address.ip()),
+            SocketAddr::V6(address) => ipv6_to_string(address.ip()),
+        };
+
+        Self {
+            link_id,
+            length,
+            remote_host,
+            remote_port: remote.port(),
+        }
     }
 }
 
-impl CommandErrorHandler for TransmissionPrepareCommand {
+impl CommandErrorHandler for RemoteTransmissionPrepareCommand {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
         StackError::TransmissionStartFailed(error)
@@ -291,12 +1623,51 @@ impl<'a, const LEN: usize> AtatCmd<LEN> for TransmissionCommand<'a> {
 impl<'a> CommandErrorHandler for TransmissionCommand<'a> {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
         StackError::SendFailed(error)
     }
 }
 
+/// Last chunk of a data upload (e.g. SYSMFG), which gets confirmed by OK
+pub struct ConfirmedTransmissionCommand<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ConfirmedTransmissionCommand<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+}
+
+impl<'a, const LEN: usize> AtatCmd<LEN> for ConfirmedTransmissionCommand<'a> {
+    type Response = NoResponse;
+
+    const MAX_TIMEOUT_MS: u32 = 5000;
+
+    fn as_bytes(&self) -> Vec<u8, LEN> {
+        Vec::from_slice(self.data).unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        resp.map_err(|_| AtError::Error)?;
+        Ok(NoResponse {})
+    }
+}
+
+impl<'a> CommandErrorHandler for ConfirmedTransmissionCommand<'a> {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::CredentialWriteError(error)
+    }
+}
+
 /// Command for receiving data
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+CIPRECVDATA", NoResponse, timeout_ms = 1_000)]
@@ -317,12 +1688,49 @@ impl<const RESP_LEN: usize> ReceiveDataCommand<RESP_LEN> {
 impl<const RESP_LEN: usize> CommandErrorHandler for ReceiveDataCommand<RESP_LEN> {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
         StackError::ReceiveFailed(error)
     }
 }
 
+/// Queries the data length buffered by ESP-AT for each link
+#[derive(Clone)]
+pub struct ReceiveLengthQueryCommand {}
+
+impl ReceiveLengthQueryCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl AtatCmd<16> for ReceiveLengthQueryCommand {
+    type Response = ReceiveLengthsResponse;
+
+    const MAX_TIMEOUT_MS: u32 = 1_000;
+
+    fn as_bytes(&self) -> Vec<u8, 16> {
+        Vec::from_slice(b"AT+CIPRECVLEN?\r\n").unwrap()
+    }
+
+    fn parse(&self, resp: Result<&[u8], InternalError>) -> Result<Self::Response, AtError> {
+        ReceiveLengthsResponse::from_response(resp.map_err(|_| AtError::Error)?)
+    }
+}
+
+impl CommandErrorHandler for ReceiveLengthQueryCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::ReceiveLengthQueryFailed(error)
+    }
+}
+
 /// Command for receiving data
 #[derive(Clone, AtatCmd)]
 #[at_cmd("+CIPCLOSE", NoResponse, timeout_ms = 1_000)]
@@ -340,6 +1748,30 @@ impl CloseSocketCommand {
 impl CommandErrorHandler for CloseSocketCommand {
     type Error = StackError;
     const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
+
+    fn command_error(&self, error: AtError) -> Self::Error {
+        StackError::CloseError(error)
+    }
+}
+
+/// Closes the connection of the single connection mode
+#[derive(Clone, AtatCmd)]
+#[at_cmd("+CIPCLOSE", NoResponse, timeout_ms = 1_000)]
+pub struct SingleCloseCommand {}
+
+impl SingleCloseCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CommandErrorHandler for SingleCloseCommand {
+    type Error = StackError;
+    const WOULD_BLOCK_ERROR: Self::Error = StackError::UnexpectedWouldBlock;
+    const PASSTHROUGH_ERROR: Self::Error = StackError::PassthroughActive;
+    const SEND_PENDING_ERROR: Self::Error = StackError::SendPending;
 
     fn command_error(&self, error: AtError) -> Self::Error {
         StackError::CloseError(error)
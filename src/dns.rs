@@ -0,0 +1,172 @@
+//! # DNS resolver
+//!
+//! This crate implements [Dns] of [embedded_nal] by using the CIPDOMAIN command of ESP-AT.
+//!
+//! Just forward lookups are supported, `get_host_by_address()` always returns [DnsError::Unsupported].
+//!
+//! By default the DNS servers provided by DHCP are used. Custom servers may be set by
+//! `set_dns_servers()` of [Adapter].
+//!
+//! ## Example
+//!
+//! ````
+//! # use embedded_nal::{AddrType, Dns};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let address = adapter.get_host_by_name("example.org", AddrType::Either).unwrap();
+//! assert_eq!("10.0.0.1", address.to_string());
+//! ````
+//!
+//! ## Custom DNS servers
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::IpAddr;
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let servers = [IpAddr::from_str("1.1.1.1").unwrap(), IpAddr::from_str("8.8.8.8").unwrap()];
+//! adapter.set_dns_servers(&servers).unwrap();
+//!
+//! let active = adapter.get_dns_servers().unwrap();
+//! assert!(active.custom);
+//! assert_eq!(&servers, active.servers.as_slice());
+//!
+//! // Switching back to DNS servers provided by DHCP
+//! adapter.reset_dns_servers().unwrap();
+//! ````
+use crate::commands::{DnsServersQueryCommand, ResolveDomainCommand, SetDnsServersCommand};
+use crate::wifi::{Adapter, DnsServers};
+use atat::AtatClient;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{AddrType, Dns, IpAddr};
+use fugit_timer::Timer;
+use heapless::String;
+
+/// Errors when resolving a host
+#[derive(Clone, Debug, PartialEq)]
+pub enum DnsError {
+    /// CIPDOMAIN command failed, e.g. domain could not be resolved
+    ResolveError(AtError),
+
+    /// Error while setting or querying the DNS servers (CIPDNS command)
+    ConfigurationError(AtError),
+
+    /// Between one and three DNS servers may be set
+    InvalidServerCount,
+
+    /// Given hostname is longer then the max. size of 64 chars
+    InvalidHostnameLength,
+
+    /// Error while parsing the resolved address
+    AddressParseError,
+
+    /// Resolved address does not match the requested address type
+    AddressTypeMismatch,
+
+    /// Operation is not supported by ESP-AT
+    Unsupported,
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Dns for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    type Error = DnsError;
+
+    /// Resolves the IP address of the given hostname
+    ///
+    /// [AddrType::Either] prefers the IPv4 address and falls back to IPv6 address.
+    fn get_host_by_name(&mut self, hostname: &str, addr_type: AddrType) -> nb::Result<IpAddr, Self::Error> {
+        if hostname.len() > 64 {
+            return nb::Result::Err(nb::Error::Other(DnsError::InvalidHostnameLength));
+        }
+
+        let command = match addr_type {
+            AddrType::IPv4 => ResolveDomainCommand::ipv4(hostname.into()),
+            AddrType::IPv6 => ResolveDomainCommand::ipv6(hostname.into()),
+            AddrType::Either => ResolveDomainCommand::either(hostname.into()),
+        };
+        let response = self.send_command(command)?;
+
+        let address = core::str::from_utf8(response.address.as_slice()).map_err(|_| DnsError::AddressParseError)?;
+        let address = IpAddr::from_str(address.trim_matches('"')).map_err(|_| DnsError::AddressParseError)?;
+
+        match (addr_type, address) {
+            (AddrType::IPv4, IpAddr::V6(_)) | (AddrType::IPv6, IpAddr::V4(_)) => {
+                nb::Result::Err(nb::Error::Other(DnsError::AddressTypeMismatch))
+            }
+            _ => nb::Result::Ok(address),
+        }
+    }
+
+    /// Reverse lookups are not supported by ESP-AT, so [DnsError::Unsupported] is returned.
+    fn get_host_by_address(&mut self, _addr: IpAddr) -> nb::Result<String<256>, Self::Error> {
+        nb::Result::Err(nb::Error::Other(DnsError::Unsupported))
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Sets custom DNS servers (one to three IPv4 or IPv6 addresses), overriding the servers provided by DHCP
+    ///
+    /// ESP-AT v1.x firmware is configured by CIPDNS_CUR, so the servers are not stored in flash.
+    /// Newer versions just support CIPDNS, which writes to flash unless disabled by `AT+SYSSTORE=0`.
+    /// So avoid calling this method repeatedly (e.g. on each connection) for preventing flash wear.
+    pub fn set_dns_servers(&mut self, servers: &[IpAddr]) -> Result<(), DnsError> {
+        if servers.is_empty() || servers.len() > 3 {
+            return Err(DnsError::InvalidServerCount);
+        }
+
+        let command = SetDnsServersCommand::custom(servers).temporary(self.capabilities.temporary_dns_configuration);
+        self.send_command(command)?;
+        Ok(())
+    }
+
+    /// Resets to the DNS servers provided by DHCP
+    pub fn reset_dns_servers(&mut self) -> Result<(), DnsError> {
+        let command = SetDnsServersCommand::dhcp().temporary(self.capabilities.temporary_dns_configuration);
+        self.send_command(command)?;
+        Ok(())
+    }
+
+    /// Returns the active DNS servers. ESP-AT v1.x does not report if custom servers are set, so `custom` is always
+    /// false there.
+    pub fn get_dns_servers(&mut self) -> Result<DnsServers, DnsError> {
+        let command = DnsServersQueryCommand::new().temporary(self.capabilities.temporary_dns_configuration);
+        self.send_command(command)
+    }
+}
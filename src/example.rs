@@ -9,6 +9,9 @@ use heapless::Deque;
 pub struct ExampleAtClient {
     /// Static URC messages
     urc_messages: Deque<&'static str, 2>,
+
+    /// True if the final response of a non-blocking join is pending
+    join_response: bool,
 }
 
 impl AtatClient for ExampleAtClient {
@@ -17,16 +20,81 @@ impl AtatClient for ExampleAtClient {
             b"AT+CWJAP=\"test_wifi\",\"secret\"\r\n" => {
                 self.urc_messages.push_back("WIFI CONNECTED\r\n").unwrap();
                 self.urc_messages.push_back("WIFI GOT IP\r\n").unwrap();
+                self.join_response = !A::EXPECTS_RESPONSE_CODE;
             }
+            b"AT+RST\r\n" => self.urc_messages.push_back("ready\r\n").unwrap(),
             b"AT+CIPSTART=0,\"TCP\",\"10.0.0.1\",21\r\n" => self.urc_messages.push_back("0,CONNECT\r\n").unwrap(),
+            b"AT+CIPSTART=0,\"TCP\",\"10.0.0.1\",8080\r\n" => {
+                self.urc_messages.push_back("0,CONNECT\r\n").unwrap();
+                self.urc_messages.push_back("+IPD,0,8:welcome!").unwrap();
+            }
+            b"AT+CIPSTART=0,\"TCP\",\"10.0.0.1\",1883,60\r\n" => self.urc_messages.push_back("0,CONNECT\r\n").unwrap(),
+            b"AT+CIPSTART=0,\"SSL\",\"10.0.0.1\",443\r\n" => self.urc_messages.push_back("0,CONNECT\r\n").unwrap(),
+            b"AT+CIPSTART=0,\"UDP\",\"10.0.0.1\",53\r\n" => self.urc_messages.push_back("0,CONNECT\r\n").unwrap(),
+            b"AT+CIPSTART=0,\"UDP\",\"0.0.0.0\",0,5353,2\r\n" => self.urc_messages.push_back("0,CONNECT\r\n").unwrap(),
+            b"AT+CIPSTART=\"TCP\",\"10.0.0.1\",21\r\n" => self.urc_messages.push_back("CONNECT\r\n").unwrap(),
+            b"AT+CIPSEND=0,6,\"10.0.0.2\",5353\r\n" => {
+                self.urc_messages.push_back("SEND OK\r\n").unwrap();
+                self.urc_messages.push_back("+IPD,0,16,\"10.0.0.2\",5353\r\n").unwrap();
+            }
+            b"AT+CIPSENDL=0,6\r\n" => self.urc_messages.push_back("SEND OK\r\n").unwrap(),
             b"AT+CIPSEND=0,6\r\n" => {
                 self.urc_messages.push_back("SEND OK\r\n").unwrap();
                 self.urc_messages.push_back("+IPD,0,16\r\n").unwrap();
             }
-            b"AT+CIPRECVDATA=0,64\r\n" => {
+            b"AT+CIPRECVDATA=0,64\r\n" | b"AT+CIPRECVDATA=0,1024\r\n" => {
                 self.urc_messages.push_back("+CIPRECVDATA,16:nice to see you!").unwrap();
             }
             b"AT+CIPCLOSE=0\r\n" => self.urc_messages.push_back("0,CLOSED\r\n").unwrap(),
+            b"AT+CIPSERVER=1,80\r\n" => self.urc_messages.push_back("0,CONNECT\r\n").unwrap(),
+            b"AT+CIPSTATE?\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CIPSTATE:0,\"TCP\",\"10.0.0.5\",51234,80,1\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
+            b"AT+CIPDOMAIN=\"example.org\",1\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CIPDOMAIN:\"10.0.0.1\"\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
+            b"AT+CIPDNS?\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CIPDNS:1,\"1.1.1.1\",\"8.8.8.8\"\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
+            b"AT+CWLAP\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CWLAP:(3,\"test_wifi\",-45,\"10:fe:ed:05:ba:51\",6)\r\n+CWLAP:(0,\"guest\",-80,\"10:fe:ed:05:ba:52\",11)\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
+            b"AT+CWSAP=\"setup_device\",\"secret_password\",6,3,10,0\r\n" => {
+                self.urc_messages.push_back("+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\n").unwrap();
+                self.urc_messages
+                    .push_back("+DIST_STA_IP:\"18:fe:34:a2:b1:09\",\"192.168.4.2\"\r\n")
+                    .unwrap();
+            }
+            b"AT+CWLIF\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CWLIF:192.168.4.2,18:fe:34:a2:b1:09\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
+            b"AT+GMR\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"AT version:3.2.0.0(s-ec2dec2 - ESP32 - Jul 28 2023 07:05:28)\r\nSDK version:v5.0.2-376-g24b9d38a24-dirty\r\ncompile time(6118fc22):Jul 28 2023 07:14:30\r\nBin version:v3.2.0.0(ESP32-WROOM-32)\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
+            b"AT+CIPSTA?\r\n" => {
+                let response = cmd
+                    .parse(Ok(b"+CIPSTA:ip:\"10.0.0.181\"\r\n+CIPSTA:gateway:\"10.0.0.1\"\r\n+CIPSTA:netmask:\"255.255.255.0\"\r\n"))
+                    .map_err(|_| nb::Error::Other(Error::Error))?;
+                return nb::Result::Ok(response);
+            }
             b"AT+CIFSR\r\n" => {
                 let response = cmd
                     .parse(Ok(
@@ -53,6 +121,12 @@ impl AtatClient for ExampleAtClient {
     }
 
     fn check_response<A: AtatCmd<LEN>, const LEN: usize>(&mut self, _cmd: &A) -> nb::Result<A::Response, Error> {
+        if self.join_response {
+            // OK response, which is not awaited by any command
+            self.join_response = false;
+            return nb::Result::Err(nb::Error::Other(Error::InvalidResponse));
+        }
+
         nb::Result::Err(nb::Error::WouldBlock)
     }
 
@@ -63,15 +137,18 @@ impl AtatClient for ExampleAtClient {
     fn reset(&mut self) {}
 }
 
-/// Timer mock
+/// Timer mock. The monotonic time advances by one millisecond per `now()` call.
 #[derive(Default)]
-pub struct ExampleTimer {}
+pub struct ExampleTimer {
+    ticks: u32,
+}
 
 impl Timer<1_000_000> for ExampleTimer {
     type Error = u32;
 
     fn now(&mut self) -> TimerInstantU32<1000000> {
-        unimplemented!()
+        self.ticks = self.ticks.wrapping_add(1_000);
+        TimerInstantU32::from_ticks(self.ticks)
     }
 
     fn start(&mut self, _duration: TimerDurationU32<1000000>) -> Result<(), Self::Error> {
@@ -0,0 +1,220 @@
+//! # Firmware information
+//!
+//! ESP-AT releases differ in the supported commands, e.g. ESP8266 firmware v2.2 lacks commands of the
+//! current ESP32 firmware v3.x. The version information (AT+GMR) is queried by `firmware_info()` of [Adapter].
+//!
+//! Based on the AT version, the [Capabilities] of the firmware are derived. They are stored by the adapter,
+//! so that features are just used if supported:
+//! * Long transmissions (CIPSENDL, s. [stack module](crate::stack)) require version 2.4 or newer.
+//!   Otherwise data is sent by CIPSEND in blocks.
+//! * IPv6 connections require version 2.1 or newer. Otherwise [Error::Ipv6Unsupported](crate::stack::Error::Ipv6Unsupported)
+//!   is returned when connecting.
+//! * Querying the length of received data (CIPRECVLEN) requires version 2.0 or newer.
+//! * Querying the connection state by CIPSTATE requires version 2.0 or newer. Otherwise the legacy CIPSTATUS
+//!   command is used.
+//!
+//! As long as no firmware information was queried, all features are considered as supported.
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::firmware::Version;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let info = adapter.firmware_info().unwrap();
+//! assert_eq!("v3.2.0.0(ESP32-WROOM-32)", info.bin_version.as_str());
+//! assert_eq!(Some(Version::new(3, 2, 0)), info.version());
+//!
+//! assert!(adapter.capabilities().long_transmission);
+//! ````
+use crate::commands::FirmwareVersionCommand;
+use crate::wifi::Adapter;
+use atat::{AtatClient, AtatResp, Error as AtError};
+use fugit_timer::Timer;
+use heapless::String;
+
+/// Errors when querying the firmware information
+#[derive(Clone, Debug, PartialEq)]
+pub enum FirmwareError {
+    /// GMR command failed or response could not be parsed
+    CommandError(AtError),
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+/// Version information of the firmware
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct FirmwareInfo {
+    /// AT version, e.g. `3.2.0.0(s-ec2dec2 - ESP32 - Jul 28 2023 07:05:28)`
+    pub at_version: String<64>,
+
+    /// SDK version, e.g. `v5.0.2-376-g24b9d38a24-dirty`
+    pub sdk_version: String<64>,
+
+    /// Compile time, e.g. `Jul 28 2023 07:14:30`
+    pub compile_time: String<32>,
+
+    /// Bin version, e.g. `v3.2.0.0(ESP32-WROOM-32)`. Empty if not reported by the firmware.
+    pub bin_version: String<64>,
+}
+
+impl AtatResp for FirmwareInfo {}
+
+impl FirmwareInfo {
+    /// Parses the response of GMR command, e.g. `AT version:2.2.0.0(...)`, one line per field
+    pub(crate) fn from_response(response: &[u8]) -> Result<Self, AtError> {
+        let response = core::str::from_utf8(response).map_err(|_| AtError::Parse)?;
+        let mut info = Self::default();
+
+        for line in response.lines() {
+            // Keys may contain additional information, e.g. `compile time(6800286):`
+            let (key, value) = match line.trim().split_once(':') {
+                None => continue,
+                Some(parts) => parts,
+            };
+
+            let value = value.trim();
+            if key.starts_with("AT version") {
+                info.at_version = Self::to_string(value)?;
+            } else if key.starts_with("SDK version") {
+                info.sdk_version = Self::to_string(value)?;
+            } else if key.starts_with("compile time") {
+                info.compile_time = Self::to_string(value)?;
+            } else if key.starts_with("Bin version") {
+                info.bin_version = Self::to_string(value)?;
+            }
+        }
+
+        if info.at_version.is_empty() {
+            return Err(AtError::Parse);
+        }
+
+        Ok(info)
+    }
+
+    /// Copies the given value, returns a parse error if the value exceeds the capacity
+    fn to_string<const N: usize>(value: &str) -> Result<String<N>, AtError> {
+        let mut string = String::new();
+        string.push_str(value).map_err(|_| AtError::Parse)?;
+        Ok(string)
+    }
+
+    /// Returns the parsed AT version, None if the version has an unknown format
+    pub fn version(&self) -> Option<Version> {
+        let version = self.at_version.split('(').next()?;
+        let mut parts = version.trim().split('.');
+
+        Some(Version::new(
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+            parts.next()?.parse().ok()?,
+        ))
+    }
+}
+
+/// AT version of the firmware
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    /// Major version
+    pub major: u8,
+
+    /// Minor version
+    pub minor: u8,
+
+    /// Patch version
+    pub patch: u8,
+}
+
+impl Version {
+    pub const fn new(major: u8, minor: u8, patch: u8) -> Self {
+        Self { major, minor, patch }
+    }
+}
+
+/// Optional features supported by the firmware
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Capabilities {
+    /// True if long transmissions by CIPSENDL command are supported
+    pub long_transmission: bool,
+
+    /// True if IPv6 connections are supported
+    pub ipv6: bool,
+
+    /// True if the length of received data may be queried by CIPRECVLEN command
+    pub receive_length_query: bool,
+
+    /// True if the connection state may be queried by CIPSTATE command. Otherwise CIPSTATUS is used.
+    pub connection_state_query: bool,
+
+    /// True if DNS servers are configured by CIPDNS_CUR command, which is not stored in flash (ESP-AT v1.x).
+    /// Otherwise CIPDNS is used, which is stored in flash if enabled by SYSSTORE.
+    pub temporary_dns_configuration: bool,
+}
+
+impl Default for Capabilities {
+    /// All features are considered as supported
+    fn default() -> Self {
+        Self {
+            long_transmission: true,
+            ipv6: true,
+            receive_length_query: true,
+            connection_state_query: true,
+            temporary_dns_configuration: false,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Derives the capabilities of the given AT version
+    pub fn from_version(version: Version) -> Self {
+        Self {
+            long_transmission: version >= Version::new(2, 4, 0),
+            ipv6: version >= Version::new(2, 1, 0),
+            receive_length_query: version >= Version::new(2, 0, 0),
+            connection_state_query: version >= Version::new(2, 0, 0),
+            temporary_dns_configuration: version < Version::new(2, 0, 0),
+        }
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Queries the version information of the firmware and updates the [Capabilities] accordingly.
+    /// If the AT version has an unknown format, the capabilities are kept.
+    pub fn firmware_info(&mut self) -> Result<FirmwareInfo, FirmwareError> {
+        let info = self.send_command(FirmwareVersionCommand::new())?;
+
+        if let Some(version) = info.version() {
+            self.capabilities = Capabilities::from_version(version);
+        }
+
+        Ok(info)
+    }
+
+    /// Returns the features supported by the firmware. All features are considered as supported until
+    /// the firmware information is queried by `firmware_info()`.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
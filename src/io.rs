@@ -0,0 +1,179 @@
+//! # embedded-io connections
+//!
+//! [TcpConnection] borrows the [Adapter] and a connected [Socket] and implements [Read] and [Write] of
+//! [embedded_io], e.g. for passing a TCP or TLS connection to HTTP or MQTT clients.
+//!
+//! Both reading and writing are blocking. `read()` returns `Ok(0)` once the socket was closed by the
+//! remote side and all buffered data has been read.
+//!
+//! Requires the `embedded-io` feature.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_io::{Read, Write};
+//! # use embedded_nal::{SocketAddr, TcpClientStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::io::TcpConnection;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.connect(&mut socket, SocketAddr::from_str("10.0.0.1:21").unwrap()).unwrap();
+//!
+//! let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+//! connection.write_all(b"hallo!").unwrap();
+//!
+//! let mut buffer = [0x0; 64];
+//! let length = connection.read(&mut buffer).unwrap();
+//! assert_eq!(b"nice to see you!", &buffer[..length]);
+//! ````
+use crate::stack::{Error, Socket};
+use crate::wifi::Adapter;
+use atat::AtatClient;
+use embedded_io::{ErrorKind, ErrorType, Read, Write};
+use embedded_nal::TcpClientStack;
+use fugit_timer::Timer;
+
+/// Connected TCP socket implementing [Read] and [Write]
+pub struct TcpConnection<
+    'a,
+    A: AtatClient,
+    T: Timer<TIMER_HZ>,
+    const TIMER_HZ: u32,
+    const TX_SIZE: usize,
+    const RX_SIZE: usize,
+    const SOCKETS: usize,
+> {
+    adapter: &'a mut Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>,
+    socket: &'a mut Socket,
+}
+
+impl<
+        'a,
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > TcpConnection<'a, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Wraps the given socket, which needs to be connected already
+    pub fn new(adapter: &'a mut Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>, socket: &'a mut Socket) -> Self {
+        Self { adapter, socket }
+    }
+
+    /// Length of the unconfirmed block of this socket
+    fn pending_length(&self) -> Option<usize> {
+        self.adapter
+            .pending_send
+            .filter(|pending| pending.link_id == self.socket.link_id)
+            .map(|pending| pending.length)
+    }
+}
+
+impl embedded_io::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ConnectError(_) => ErrorKind::ConnectionRefused,
+            Error::SocketUnconnected => ErrorKind::NotConnected,
+            Error::ClosingSocket => ErrorKind::ConnectionReset,
+            Error::AlreadyConnected => ErrorKind::AlreadyExists,
+            Error::ServerAlreadyRunning => ErrorKind::AddrInUse,
+            Error::NoSocketAvailable => ErrorKind::OutOfMemory,
+            Error::PartialSend => ErrorKind::WriteZero,
+            Error::ReceiveOverflow => ErrorKind::InvalidData,
+            Error::DatagramTooLarge
+            | Error::UnboundSocket
+            | Error::BoundSocket
+            | Error::NotListening
+            | Error::InvalidTlsConfiguration
+            | Error::InvalidCredential => ErrorKind::InvalidInput,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl<
+        'a,
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > ErrorType for TcpConnection<'a, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    type Error = Error;
+}
+
+impl<
+        'a,
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Read for TcpConnection<'a, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Blocks until data is available. Returns `Ok(0)` if the socket was closed by the remote side.
+    /// A block still pending from `write()` gets confirmed first.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.flush()?;
+
+        loop {
+            match TcpClientStack::receive(self.adapter, self.socket, buf) {
+                Ok(length) => return Ok(length),
+                Err(nb::Error::Other(error)) => return Err(error),
+                Err(nb::Error::WouldBlock) => {
+                    if !self.adapter.is_connected(self.socket)? {
+                        return Ok(0);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<
+        'a,
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Write for TcpConnection<'a, A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Blocks until at least one block is confirmed. The returned length includes the last block, which may still
+    /// be unconfirmed. It gets confirmed by `flush()` or by the next call of `write()` or `read()`.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.flush()?;
+        let sent = nb::block!(TcpClientStack::send(self.adapter, self.socket, buf))?;
+
+        Ok(sent + self.pending_length().unwrap_or(0))
+    }
+
+    /// Blocks until the last block written by `write()` is confirmed
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.pending_length().is_some() {
+            nb::block!(self.adapter.poll_send_confirmation())?;
+        }
+
+        Ok(())
+    }
+}
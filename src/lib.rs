@@ -4,13 +4,30 @@
 //! implementing [embedded-nal](embedded_nal) based on [ATAT](https://crates.io/crates/atat).
 //!
 //! Currently this crates offers the following features
-//! * Joining an WIFI access point, s. [wifi module](crate::wifi)
+//! * Scanning for and joining an WIFI access point, s. [wifi module](crate::wifi)
+//! * SoftAP including station management, s. [soft_ap module](crate::soft_ap)
 //! * TCP client stack (multi socket), s. [stack module](crate::stack)
+//! * TCP server (single server, multiple clients), s. [stack module](crate::stack)
+//! * Long TCP transmissions (CIPSENDL) for bulk data, s. [stack module](crate::stack)
+//! * Resynchronization of the connection states (CIPSTATE), s. [stack module](crate::stack)
+//! * TCP socket options like keep-alive and Nagle's algorithm, s. [stack module](crate::stack)
+//! * Single TCP connection in passthrough mode, s. [passthrough module](crate::passthrough)
+//! * TLS client connections, s. [tls module](crate::tls)
+//! * UDP client and full stack (multi socket), s. [udp module](crate::udp)
+//! * DNS resolving, s. [dns module](crate::dns)
+//! * Firmware version and capability probing, s. [firmware module](crate::firmware)
+//! * Modem reset and detection of unexpected restarts, s. [reset module](crate::reset)
+//! * Active receiving mode with internal buffering (feature `active-mode`), s. `active` module
+//! * [embedded-io](https://crates.io/crates/embedded-io) Read/Write for TCP connections (feature `embedded-io`), s. `io` module
 //!
 //! ## Setup
 //! This crates is based on [ATAT](atat) and requires a AtClient instance.
 //! s. [examples](https://github.com/BlackbirdHQ/atat/tree/master/atat/examples).
 //!
+//! Additionally a [fugit_timer::Timer] is required. Besides blocking waits by `start()` and `wait()`, send
+//! timeouts are measured by `now()`. So `now()` needs to be free-running and monotonic, i.e. it must not be
+//! reset by `start()`.
+//!
 //! ## Example
 //!
 //! ````
@@ -41,10 +58,21 @@
 #[cfg(test)]
 extern crate alloc;
 
+#[cfg(feature = "active-mode")]
+pub mod active;
 pub(crate) mod commands;
+pub mod dns;
 pub mod example;
+pub mod firmware;
+#[cfg(feature = "embedded-io")]
+pub mod io;
+pub mod passthrough;
+pub mod reset;
 pub(crate) mod responses;
+pub mod soft_ap;
 pub mod stack;
+pub mod tls;
+pub mod udp;
 pub mod urc;
 pub mod wifi;
 
@@ -0,0 +1,216 @@
+//! # Passthrough mode
+//!
+//! As alternative to the multi socket stack, ESP-AT supports a single TCP connection in passthrough
+//! (transparent transmission) mode. Data is forwarded to the remote without any CIPSEND framing, which
+//! avoids the command overhead for high-throughput streams.
+//!
+//! The connection is opened by `connect_passthrough()` of [Adapter], which switches ESP-AT to single
+//! connection mode (CIPMUX=0) and passthrough mode (CIPMODE=1). So no sockets of the [stack module](crate::stack)
+//! or [udp module](crate::udp) may be open at this time. While passthrough mode is active, ESP-AT does not
+//! process any AT commands, so all other methods of [Adapter] return `PassthroughActive` errors.
+//! `close_passthrough()` leaves the passthrough mode by the `+++` escape sequence and closes the connection.
+//! Afterwards the regular sockets may be used again.
+//!
+//! Received data is forwarded by ESP-AT as it is, without any URC framing. So the serial RX must not be fed
+//! to the ATAT ingress while passthrough mode is active. Instead the data is read by `receive_passthrough()`
+//! directly from the serial interface.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::SocketAddr;
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let mut socket = adapter.connect_passthrough(SocketAddr::from_str("10.0.0.1:21").unwrap()).unwrap();
+//! adapter.send_passthrough(&mut socket, b"hallo!").unwrap();
+//! ````
+use crate::commands::{
+    PassthroughSendCommand, SetMultipleConnectionsCommand, SingleCloseCommand, SingleConnectCommand,
+    TransmissionCommand, TransmissionModeCommand,
+};
+use crate::stack::{Error, SocketState};
+use crate::wifi::Adapter;
+use atat::{AtatClient, Error as AtError};
+use embedded_hal::serial;
+use embedded_nal::SocketAddr;
+use fugit::TimerDurationU32;
+use fugit_timer::Timer;
+
+/// Escape sequence for leaving the passthrough mode
+const ESCAPE_SEQUENCE: &[u8] = b"+++";
+
+/// Min. time in milliseconds without data transmission before sending the escape sequence
+const ESCAPE_GUARD_BEFORE_MS: u32 = 20;
+
+/// Min. time in milliseconds after the escape sequence until ESP-AT accepts commands
+const ESCAPE_GUARD_AFTER_MS: u32 = 1_000;
+
+/// Connection of the passthrough mode
+#[derive(Debug, PartialEq)]
+pub struct PassthroughSocket {
+    /// Remote address of the connection
+    remote: SocketAddr,
+}
+
+impl PassthroughSocket {
+    pub(crate) fn new(remote: SocketAddr) -> Self {
+        Self { remote }
+    }
+
+    /// Returns the remote address of the connection
+    pub fn remote(&self) -> SocketAddr {
+        self.remote
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Opens a TCP connection in passthrough mode. Both IPv4 and IPv6 are supported.
+    /// Returns [Error::SocketsInUse] if any socket is open or the TCP server is running.
+    pub fn connect_passthrough(&mut self, remote: SocketAddr) -> Result<PassthroughSocket, Error> {
+        self.process_urc_messages();
+
+        if self.passthrough_active {
+            return Err(Error::PassthroughActive);
+        }
+
+        self.assert_remote_supported(&remote)?;
+
+        if self.server_port.is_some() || self.sockets.iter().any(|state| state != &SocketState::Closed) {
+            return Err(Error::SocketsInUse);
+        }
+
+        self.send_command(SetMultipleConnectionsCommand::single())?;
+        self.multi_connections_enabled = false;
+        self.send_command(TransmissionModeCommand::passthrough())?;
+
+        if let Err(error) = self.start_passthrough(remote) {
+            // Best effort for getting back to normal mode, so that regular sockets may be used
+            if self.single_connected {
+                let _ = self.send_command(SingleCloseCommand::new());
+                self.single_connected = false;
+            }
+            let _ = self.send_command(TransmissionModeCommand::normal());
+
+            return Err(error);
+        }
+
+        Ok(PassthroughSocket::new(remote))
+    }
+
+    /// Sends the given buffer and returns the length (in bytes) sent.
+    /// The data is divided into smaller blocks. The block size is determined by the generic constant TX_SIZE.
+    ///
+    /// Note: A block consisting just of `+++` is interpreted as escape sequence by ESP-AT.
+    pub fn send_passthrough(&mut self, _socket: &mut PassthroughSocket, buffer: &[u8]) -> Result<usize, Error> {
+        if !self.passthrough_active {
+            return Err(Error::SocketUnconnected);
+        }
+
+        for chunk in buffer.chunks(TX_SIZE) {
+            self.send_raw::<TransmissionCommand<'_>, TX_SIZE>(TransmissionCommand::new(chunk))?;
+        }
+
+        Ok(buffer.len())
+    }
+
+    /// Reads the received data from the given serial interface and returns the length (in bytes) read.
+    /// Returns [nb::Error::WouldBlock] if no data is available.
+    ///
+    /// Data is read directly, bypassing ATAT, as ESP-AT forwards the received data without URC framing.
+    pub fn receive_passthrough<R: serial::Read<u8>>(
+        &mut self,
+        _socket: &mut PassthroughSocket,
+        rx: &mut R,
+        buffer: &mut [u8],
+    ) -> nb::Result<usize, Error> {
+        if !self.passthrough_active {
+            return nb::Result::Err(nb::Error::Other(Error::SocketUnconnected));
+        }
+
+        let mut length = 0;
+        while length < buffer.len() {
+            match rx.read() {
+                Ok(byte) => {
+                    buffer[length] = byte;
+                    length += 1;
+                }
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(_)) => {
+                    return nb::Result::Err(nb::Error::Other(Error::ReceiveFailed(AtError::Read)))
+                }
+            }
+        }
+
+        if length == 0 {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        nb::Result::Ok(length)
+    }
+
+    /// Leaves the passthrough mode by the escape sequence and closes the connection.
+    /// Blocks for the guard time of around one second required by ESP-AT.
+    ///
+    /// In case of an error the passthrough mode is internally considered as closed, so that it's not lost.
+    pub fn close_passthrough(&mut self, _socket: PassthroughSocket) -> Result<(), Error> {
+        if self.passthrough_active {
+            self.wait_guard_time(ESCAPE_GUARD_BEFORE_MS)?;
+            self.passthrough_active = false;
+            self.send_command::<TransmissionCommand<'_>, TX_SIZE>(TransmissionCommand::new(ESCAPE_SEQUENCE))?;
+            self.wait_guard_time(ESCAPE_GUARD_AFTER_MS)?;
+        }
+
+        self.send_command(TransmissionModeCommand::normal())?;
+        self.process_urc_messages();
+
+        if self.single_connected {
+            self.send_command(SingleCloseCommand::new())?;
+            self.single_connected = false;
+        }
+
+        Ok(())
+    }
+
+    /// Establishes the connection and starts the passthrough transmission
+    fn start_passthrough(&mut self, remote: SocketAddr) -> Result<(), Error> {
+        self.single_connected = false;
+
+        let command = match remote {
+            SocketAddr::V4(address) => SingleConnectCommand::tcp_v4(address),
+            SocketAddr::V6(address) => SingleConnectCommand::tcp_v6(address),
+        };
+        let result = self.send_command(command);
+        self.process_urc_messages();
+        result?;
+
+        if !self.single_connected {
+            return Err(Error::UnconfirmedSocketState);
+        }
+
+        self.send_command(PassthroughSendCommand::new())?;
+        self.passthrough_active = true;
+        Ok(())
+    }
+
+    /// Blocks for the given time in milliseconds
+    fn wait_guard_time(&mut self, duration_ms: u32) -> Result<(), Error> {
+        self.timer
+            .start(TimerDurationU32::millis(duration_ms))
+            .map_err(|_| Error::TimerError)?;
+        nb::block!(self.timer.wait()).map_err(|_| Error::TimerError)
+    }
+}
@@ -0,0 +1,176 @@
+//! # Modem reset
+//!
+//! ESP-AT signals each boot by a `ready` message. If the modem restarts unexpectedly, e.g. caused by a
+//! brown-out or the watchdog, all connections and settings of the running session are lost. [Adapter] handles
+//! the `ready` message automatically: The cached state (multiple connections, receiving mode, WIFI state,
+//! TCP server) is invalidated, so that the required commands are sent again, and all connected sockets are
+//! marked as closed. Such sockets need to be closed by calling `close()`.
+//!
+//! Unexpected restarts are signaled once by `modem_restarted()`, so that the application may join the
+//! access point again.
+//!
+//! The modem is restarted on purpose by `reset()` (AT+RST command) or by `hardware_reset()`, which
+//! toggles the reset pin by the given callback. Both block until ESP-AT is ready again.
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, WifiAdapter};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! adapter.reset().unwrap();
+//! adapter.join("test_wifi", "secret").unwrap();
+//!
+//! // Somewhere in the main loop
+//! if adapter.modem_restarted() {
+//!     adapter.join("test_wifi", "secret").unwrap();
+//! }
+//! ````
+use crate::commands::RestartCommand;
+use crate::stack::SocketState;
+use crate::wifi::Adapter;
+use atat::AtatClient;
+use atat::Error as AtError;
+use fugit::TimerDurationU32;
+use fugit_timer::Timer;
+
+/// Max. time in milliseconds until ESP-AT is ready after a reset
+const RESET_TIMEOUT_MS: u32 = 5_000;
+
+/// Errors when resetting the modem
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResetError {
+    /// RST command failed
+    CommandError(AtError),
+
+    /// ESP-AT did not signal readiness in time
+    Timeout,
+
+    /// Error of the timer used for timeout measurement
+    TimerError,
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Restarts the modem by AT+RST command and blocks until ESP-AT is ready again.
+    /// The restart is not signaled by `modem_restarted()`. An unconfirmed transmission is discarded.
+    pub fn reset(&mut self) -> Result<(), ResetError> {
+        self.process_urc_messages();
+        self.restarted = false;
+        self.pending_send = None;
+
+        self.send_command(RestartCommand::new())?;
+        self.wait_ready()
+    }
+
+    /// Restarts the modem by the given callback, which is expected to toggle the reset (EN) pin.
+    /// Blocks until ESP-AT is ready again. The restart is not signaled by `modem_restarted()`.
+    pub fn hardware_reset<F: FnOnce()>(&mut self, reset_pin: F) -> Result<(), ResetError> {
+        self.process_urc_messages();
+        self.restarted = false;
+
+        reset_pin();
+        self.wait_ready()
+    }
+
+    /// Returns true once if the modem restarted unexpectedly since the last call.
+    /// Afterwards the access point needs to be joined again, the SoftAP needs to be restarted and sockets need to be
+    /// reconnected.
+    pub fn modem_restarted(&mut self) -> bool {
+        self.process_urc_messages();
+        core::mem::take(&mut self.restarted)
+    }
+
+    /// Invalidates all cached state after ESP-AT signaled a restart
+    pub(crate) fn handle_restart(&mut self) {
+        self.restarted = true;
+
+        self.joined = false;
+        self.ip_assigned = false;
+        self.join_pending = false;
+        self.join_response = None;
+
+        self.multi_connections_enabled = false;
+        self.station_enabled = false;
+        self.soft_ap_enabled = false;
+        self.passthrough_active = false;
+        self.single_connected = false;
+        self.receiving_mode_configured = false;
+        self.data_info_enabled = false;
+        self.server_port = None;
+        self.unsupported_link_id = None;
+        self.station_events.clear();
+
+        // Unconfirmed transmission is lost
+        self.pending_send = None;
+        self.send_progress = None;
+        self.send_confirmed = None;
+        self.recv_byte_count = None;
+        self.already_connected = false;
+        self.connecting = [false; SOCKETS];
+
+        // Data buffered by ESP-AT is lost
+        self.data_available = [0; SOCKETS];
+        self.receive_poll_deadlines = [None; SOCKETS];
+        self.data = None;
+        self.data_remote = None;
+
+        #[cfg(feature = "active-mode")]
+        {
+            self.buffers.iter_mut().for_each(|buffer| buffer.clear());
+            self.buffer_overflow = [false; SOCKETS];
+        }
+
+        for state in self.sockets.iter_mut() {
+            match state {
+                SocketState::Connected => *state = SocketState::Closing,
+                SocketState::Incoming => *state = SocketState::Closed,
+                _ => {}
+            }
+        }
+    }
+
+    /// Blocks until ESP-AT signals readiness
+    fn wait_ready(&mut self) -> Result<(), ResetError> {
+        self.timer
+            .start(TimerDurationU32::millis(RESET_TIMEOUT_MS))
+            .map_err(|_| ResetError::TimerError)?;
+
+        loop {
+            self.process_urc_messages();
+
+            if self.restarted {
+                self.restarted = false;
+                return Ok(());
+            }
+
+            match self.timer.wait() {
+                Ok(_) => return Err(ResetError::Timeout),
+                Err(nb::Error::Other(_)) => return Err(ResetError::TimerError),
+                Err(nb::Error::WouldBlock) => {}
+            }
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use atat::atat_derive::AtatResp;
-use atat::heapless::String;
+use atat::heapless::{String, Vec};
 use atat::heapless_bytes::Bytes;
+use atat::AtatResp;
+use atat::Error as AtError;
+
+/// Max. number of links reported by ESP-AT, which is the upper limit of `CONFIG_AT_SOCKET_MAX_CONN_NUM`
+pub(crate) const MAX_LINKS: usize = 16;
 
 /// Commands which gets just responded by OK
 #[derive(Clone, AtatResp)]
@@ -19,3 +24,136 @@ pub struct LocalAddressResponse {
     /// String encoded address
     pub address: String<64>,
 }
+
+/// Single line response of CWLAP command. Fields are selected by CWLAPOPT command.
+#[derive(Clone, AtatResp, Debug)]
+pub struct AccessPointResponse {
+    /// Encryption type
+    pub ecn: u8,
+
+    /// SSID of the access point
+    pub ssid: String<32>,
+
+    /// Signal strength
+    pub rssi: i8,
+
+    /// MAC address of the access point (BSSID)
+    pub mac: String<17>,
+
+    /// WIFI channel
+    pub channel: u8,
+}
+
+/// Single line response of CWLIF command
+#[derive(Clone, AtatResp, Debug)]
+pub struct StationResponse {
+    /// IP address of the station
+    pub ip: Bytes<41>,
+
+    /// MAC address of the station
+    pub mac: Bytes<19>,
+}
+
+/// Single line response of CIPSTATE command
+#[derive(Clone, AtatResp, Debug)]
+pub struct ConnectionStateResponse {
+    /// Socket ID
+    pub link_id: usize,
+
+    /// Connection type, e.g. TCP, UDP, SSL, etc.
+    pub connection_type: String<8>,
+
+    /// Remote IPv4 or IPv6 address
+    pub remote_ip: String<64>,
+
+    /// Remote port
+    pub remote_port: u16,
+
+    /// Local port
+    pub local_port: u16,
+
+    /// 0: ESP-AT runs as client, 1: ESP-AT runs as server
+    pub tetype: u8,
+}
+
+/// Response of CIPDOMAIN command
+#[derive(Clone, AtatResp, Debug)]
+pub struct ResolveDomainResponse {
+    /// Resolved IPv4 or IPv6 address. Quoted by newer firmware versions.
+    pub address: Bytes<41>,
+}
+
+impl ConnectionStateResponse {
+    /// Parses the response of the legacy CIPSTATUS command, e.g. `+CIPSTATUS:0,"TCP","10.0.0.1",21,51234,0`,
+    /// one line per link. The leading `STATUS:` line is ignored.
+    pub(crate) fn from_status_response(response: &[u8]) -> Result<Vec<Self, MAX_LINKS>, AtError> {
+        let response = core::str::from_utf8(response).map_err(|_| AtError::Parse)?;
+        let mut states = Vec::new();
+
+        for line in response.lines() {
+            let line = match line.trim().strip_prefix("+CIPSTATUS:") {
+                None => continue,
+                Some(line) => line,
+            };
+
+            let mut parts = line.split(',').map(|part| part.trim_matches('"'));
+            let state = Self {
+                link_id: Self::parse_number(parts.next())?,
+                connection_type: Self::to_string(parts.next())?,
+                remote_ip: Self::to_string(parts.next())?,
+                remote_port: Self::parse_number(parts.next())?,
+                local_port: Self::parse_number(parts.next())?,
+                tetype: Self::parse_number(parts.next())?,
+            };
+
+            states.push(state).map_err(|_| AtError::Parse)?;
+        }
+
+        Ok(states)
+    }
+
+    fn parse_number<N: core::str::FromStr>(part: Option<&str>) -> Result<N, AtError> {
+        part.ok_or(AtError::Parse)?.parse().map_err(|_| AtError::Parse)
+    }
+
+    fn to_string<const N: usize>(part: Option<&str>) -> Result<String<N>, AtError> {
+        let mut string = String::new();
+        string.push_str(part.ok_or(AtError::Parse)?).map_err(|_| AtError::Parse)?;
+        Ok(string)
+    }
+}
+
+/// Response of CIPRECVLEN query command
+#[derive(Clone, Debug)]
+pub struct ReceiveLengthsResponse {
+    /// Data length buffered by ESP-AT, index = link_id
+    pub lengths: Vec<usize, MAX_LINKS>,
+}
+
+impl AtatResp for ReceiveLengthsResponse {}
+
+impl ReceiveLengthsResponse {
+    /// Parses the response of CIPRECVLEN query, e.g. `+CIPRECVLEN:12,0,,-1,0`.
+    /// Unused links are either reported empty or by -1, which is treated as no data available.
+    pub(crate) fn from_response(response: &[u8]) -> Result<Self, AtError> {
+        let response = core::str::from_utf8(response).map_err(|_| AtError::Parse)?;
+        let line = response
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("+CIPRECVLEN:"))
+            .ok_or(AtError::Parse)?;
+
+        let mut lengths = Vec::new();
+        for part in line.split(',') {
+            let part = part.trim();
+            let length = if part.is_empty() || part.starts_with('-') {
+                0
+            } else {
+                part.parse().map_err(|_| AtError::Parse)?
+            };
+
+            lengths.push(length).map_err(|_| AtError::Parse)?;
+        }
+
+        Ok(Self { lengths })
+    }
+}
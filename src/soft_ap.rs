@@ -0,0 +1,284 @@
+//! # SoftAP
+//!
+//! Besides joining a network, ESP-AT may act as access point (SoftAP) itself, e.g. for the first-time setup
+//! of headless devices. The SoftAP is started by `start_soft_ap()` of [Adapter].
+//!
+//! Station and SoftAP may be used at the same time: If the station mode is used by joining a network
+//! (before or after starting the SoftAP), ESP-AT is switched to SoftAP+Station mode.
+//!
+//! Connecting and disconnecting stations are signaled by [StationEvent], which are queued (max. 8 events)
+//! and may be polled by `poll_station_event()`.
+//!
+//! ## Example
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::soft_ap::{SoftApConfig, StationEvent};
+//! # use esp_at_nal::wifi::{Adapter, Encryption};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let config = SoftApConfig {
+//!     ssid: "setup_device",
+//!     password: "secret_password",
+//!     channel: 6,
+//!     encryption: Encryption::Wpa2Psk,
+//!     ..SoftApConfig::default()
+//! };
+//! adapter.start_soft_ap(&config).unwrap();
+//!
+//! // Waiting for a station
+//! let event = adapter.poll_station_event().unwrap();
+//! assert!(matches!(event, StationEvent::Connected(_)));
+//!
+//! // Listing connected stations
+//! let stations = adapter.get_stations().unwrap();
+//! assert_eq!("192.168.4.2", stations[0].ip.to_string());
+//! assert_eq!("18:fe:34:a2:b1:09", stations[0].mac.as_str());
+//! ````
+use crate::commands::{ListStationsCommand, SoftApConfigurationCommand, WifiModeCommand};
+use crate::responses::StationResponse;
+use crate::wifi::{Adapter, Encryption};
+use atat::{AtatClient, Error as AtError};
+use core::str::FromStr;
+use embedded_nal::IpAddr;
+use fugit_timer::Timer;
+use heapless::{String, Vec};
+
+/// Configuration of the SoftAP
+#[derive(Clone, Debug)]
+pub struct SoftApConfig<'a> {
+    /// SSID of the SoftAP. Max. length: 32 chars
+    pub ssid: &'a str,
+
+    /// Password of the SoftAP. Length: 8-64 chars, ignored for open networks
+    pub password: &'a str,
+
+    /// WIFI channel (1-13)
+    pub channel: u8,
+
+    /// Encryption type. Supported: Open, WPA PSK, WPA2 PSK and WPA/WPA2 PSK
+    pub encryption: Encryption,
+
+    /// Max. number of connected stations (1-10)
+    pub max_connections: usize,
+
+    /// True if the SSID should not be broadcasted
+    pub hidden: bool,
+}
+
+impl<'a> Default for SoftApConfig<'a> {
+    fn default() -> Self {
+        Self {
+            ssid: "",
+            password: "",
+            channel: 1,
+            encryption: Encryption::Wpa2Psk,
+            max_connections: 10,
+            hidden: false,
+        }
+    }
+}
+
+/// Errors of SoftAP operations
+#[derive(Clone, Debug, PartialEq)]
+pub enum SoftApError {
+    /// Error while setting the WIFI mode
+    ModeError(AtError),
+
+    /// CWSAP command failed
+    ConfigurationError(AtError),
+
+    /// CWLIF command failed
+    StationListError(AtError),
+
+    /// Given SSID is empty or longer then the max. size of 32 chars
+    InvalidSsidLength,
+
+    /// Given password is shorter then 8 or longer then 64 chars
+    InvalidPasswordLength,
+
+    /// Given channel is out of range 1-13
+    InvalidChannel,
+
+    /// Given encryption type is not supported by SoftAP
+    InvalidEncryption,
+
+    /// Given max. connections is out of range 1-10
+    InvalidMaxConnections,
+
+    /// Unable to parse the address of a station
+    AddressParseError,
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+/// Station connected to the SoftAP
+#[derive(Clone, Debug)]
+pub struct Station {
+    /// IP address assigned to the station
+    pub ip: IpAddr,
+
+    /// MAC address of the station
+    pub mac: String<17>,
+}
+
+impl Station {
+    pub(crate) fn from_response(response: StationResponse) -> Result<Self, SoftApError> {
+        let ip = core::str::from_utf8(response.ip.as_slice()).map_err(|_| SoftApError::AddressParseError)?;
+        let mac = core::str::from_utf8(response.mac.as_slice()).map_err(|_| SoftApError::AddressParseError)?;
+        let mac = mac.trim_matches('"');
+
+        if mac.len() > 17 {
+            return Err(SoftApError::AddressParseError);
+        }
+
+        Ok(Self {
+            ip: IpAddr::from_str(ip.trim_matches('"')).map_err(|_| SoftApError::AddressParseError)?,
+            mac: String::from(mac),
+        })
+    }
+}
+
+/// Station related events of the SoftAP
+#[derive(Clone, Debug, PartialEq)]
+pub enum StationEvent {
+    /// Station with the given MAC address connected
+    Connected(String<17>),
+
+    /// Station with the given MAC address disconnected
+    Disconnected(String<17>),
+
+    /// IP address was assigned to the station with the given MAC address
+    IpAssigned(String<17>, IpAddr),
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Configures and starts the SoftAP.
+    /// Switches to SoftAP+Station mode if station mode is used, otherwise to SoftAP mode.
+    pub fn start_soft_ap(&mut self, config: &SoftApConfig) -> Result<(), SoftApError> {
+        let command = Self::soft_ap_configuration(config)?;
+
+        let mode = match self.station_enabled {
+            true => WifiModeCommand::station_soft_ap_mode(),
+            false => WifiModeCommand::soft_ap_mode(),
+        };
+        self.set_soft_ap_mode(mode)?;
+        self.soft_ap_enabled = true;
+
+        self.send_command(command)?;
+        Ok(())
+    }
+
+    /// Stops the SoftAP. Switches back to station mode if used, otherwise WIFI RF gets disabled.
+    /// Queued station events are discarded.
+    pub fn stop_soft_ap(&mut self) -> Result<(), SoftApError> {
+        let mode = match self.station_enabled {
+            true => WifiModeCommand::station_mode(),
+            false => WifiModeCommand::null_mode(),
+        };
+        self.set_soft_ap_mode(mode)?;
+
+        self.soft_ap_enabled = false;
+        self.station_events.clear();
+        Ok(())
+    }
+
+    /// Returns the stations connected to the SoftAP (max. 10)
+    pub fn get_stations(&mut self) -> Result<Vec<Station, 10>, SoftApError> {
+        let responses = self.send_command(ListStationsCommand::new())?;
+        responses.into_iter().map(Station::from_response).collect()
+    }
+
+    /// Returns the oldest queued station event
+    pub fn poll_station_event(&mut self) -> Option<StationEvent> {
+        self.process_urc_messages();
+        self.station_events.pop_front()
+    }
+
+    /// Queues the given station event. If the queue is full, the oldest event is dropped.
+    pub(crate) fn push_station_event(&mut self, event: StationEvent) {
+        if self.station_events.is_full() {
+            self.station_events.pop_front();
+        }
+
+        let _ = self.station_events.push_back(event);
+    }
+
+    /// Validates the configuration and creates the CWSAP command
+    fn soft_ap_configuration(config: &SoftApConfig) -> Result<SoftApConfigurationCommand, SoftApError> {
+        if config.ssid.is_empty() || config.ssid.len() > 32 {
+            return Err(SoftApError::InvalidSsidLength);
+        }
+
+        let ecn = match config.encryption {
+            Encryption::Open => 0,
+            Encryption::WpaPsk => 2,
+            Encryption::Wpa2Psk => 3,
+            Encryption::WpaWpa2Psk => 4,
+            _ => return Err(SoftApError::InvalidEncryption),
+        };
+
+        let password = match config.encryption {
+            Encryption::Open => "",
+            _ => config.password,
+        };
+
+        if ecn != 0 && (password.len() < 8 || password.len() > 64) {
+            return Err(SoftApError::InvalidPasswordLength);
+        }
+
+        if config.channel < 1 || config.channel > 13 {
+            return Err(SoftApError::InvalidChannel);
+        }
+
+        if config.max_connections < 1 || config.max_connections > 10 {
+            return Err(SoftApError::InvalidMaxConnections);
+        }
+
+        Ok(SoftApConfigurationCommand::new(
+            config.ssid.into(),
+            password.into(),
+            config.channel,
+            ecn,
+            config.max_connections,
+            config.hidden,
+        ))
+    }
+
+    /// Sends the given WIFI mode command. Errors are mapped directly, as the command is shared with station mode.
+    fn set_soft_ap_mode(&mut self, command: WifiModeCommand) -> Result<(), SoftApError> {
+        if self.passthrough_active {
+            return Err(SoftApError::PassthroughActive);
+        }
+
+        if self.pending_send.is_some() {
+            return Err(SoftApError::SendPending);
+        }
+
+        match self.client.send(&command) {
+            Ok(_) => Ok(()),
+            Err(nb::Error::Other(error)) => Err(SoftApError::ModeError(error)),
+            Err(nb::Error::WouldBlock) => Err(SoftApError::UnexpectedWouldBlock),
+        }
+    }
+}
@@ -1,6 +1,6 @@
-//! # TCP client stack
+//! # TCP stack
 //!
-//! This crate fully implements [TcpClientStack] of [embedded_nal].
+//! This crate fully implements [TcpClientStack] and [TcpFullStack] of [embedded_nal].
 //!
 //! Block/chunk size is defined a const generics, s. [Adapter] for more details.
 //!
@@ -32,16 +32,129 @@
 //! // Closing socket
 //! adapter.close(socket).unwrap();
 //! ````
+//!
+//! ## Server example
+//!
+//! ESP-AT supports just a single TCP server. Link IDs of incoming connections are assigned by ESP-AT, so
+//! the listening socket releases its link ID when calling `bind()`.
+//!
+//! ````
+//! # use embedded_nal::{TcpClientStack, TcpFullStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! // Starting the TCP server
+//! let mut server = adapter.socket().unwrap();
+//! adapter.bind(&mut server, 80).unwrap();
+//! adapter.listen(&mut server).unwrap();
+//!
+//! // Accepting a client connection
+//! let (mut connection, remote) = nb::block!(adapter.accept(&mut server)).unwrap();
+//! assert_eq!("10.0.0.5:51234", remote.to_string());
+//!
+//! adapter.send(&mut connection, b"hallo!").unwrap();
+//! adapter.close(connection).unwrap();
+//!
+//! // Stopping the server
+//! adapter.close(server).unwrap();
+//! ````
+//!
+//! ## Long transmissions
+//!
+//! By default each TX_SIZE block is sent by a separate CIPSEND command, which is confirmed by ESP-AT before
+//! the next block gets transmitted. For bulk data this handshake is slow. Newer firmware versions support
+//! CIPSENDL, which transmits data of arbitrary length and confirms just the complete transmission. The send
+//! timeout is restarted on each progress report of ESP-AT.
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::{SocketAddr, TcpClientStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//! adapter.set_long_transmission(true);
+//!
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.connect(&mut socket, SocketAddr::from_str("10.0.0.1:21").unwrap()).unwrap();
+//! adapter.send(&mut socket, b"hallo!").unwrap();
+//! ````
+//!
+//! ## Connection state
+//!
+//! The socket states are tracked by URC messages of ESP-AT. If such a message is missed, e.g. caused by a
+//! buffer overflow of the AT client, the cached state drifts from the actual state. `resync_connections()`
+//! queries the details of all connections and reconciles the socket states accordingly.
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::SocketAddr;
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let connections = adapter.resync_connections().unwrap();
+//! assert_eq!(1, connections.len());
+//! assert_eq!(SocketAddr::from_str("10.0.0.5:51234").unwrap(), connections[0].remote);
+//! assert_eq!(80, connections[0].local_port);
+//! ````
+//!
+//! Likewise received data is signaled by +IPD messages. If such a message is missed, the data stays buffered
+//! by ESP-AT until the next message. `query_receive_lengths()` refreshes the available data of all links by
+//! CIPRECVLEN command. By `set_receive_length_polling()` the query is done automatically once `receive()`
+//! returned no data for the given interval.
+//!
+//! ## Socket options
+//!
+//! Linger, Nagle's algorithm, send timeout and TCP keep-alive are configured per socket by [SocketOptions].
+//! The options are applied when connecting, so they need to be set before calling `connect()`.
+//! Keep-alive probes detect dead peers of long-lived connections, which are then closed by ESP-AT.
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::{SocketAddr, TcpClientStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::stack::SocketOptions;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let options = SocketOptions {
+//!     no_delay: true,
+//!     keep_alive: Some(60),
+//!     ..SocketOptions::default()
+//! };
+//!
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.set_socket_options(&socket, options).unwrap();
+//! adapter.connect(&mut socket, SocketAddr::from_str("10.0.0.1:1883").unwrap()).unwrap();
+//! ````
 use crate::commands::{
-    CloseSocketCommand, ConnectCommand, ReceiveDataCommand, SetMultipleConnectionsCommand,
-    SetSocketReceivingModeCommand, TransmissionCommand, TransmissionPrepareCommand,
+    CloseSocketCommand, CommandErrorHandler, ConnectCommand, ConnectionStateCommand, ConnectionStatusCommand,
+    LongTransmissionPrepareCommand, ReceiveDataCommand, ReceiveLengthQueryCommand, ServerCommand,
+    ServerMaxConnectionsCommand, SetDataInfoCommand, SetMultipleConnectionsCommand, SetSocketReceivingModeCommand,
+    SocketOptionsCommand, TransmissionCommand, TransmissionPrepareCommand,
 };
+use crate::responses::{ConnectionStateResponse, MAX_LINKS};
 use crate::wifi::Adapter;
-use atat::AtatClient;
 use atat::Error as AtError;
-use embedded_nal::{SocketAddr, TcpClientStack};
+use atat::{AtatClient, AtatCmd};
+use core::str::FromStr;
+use embedded_nal::{IpAddr, SocketAddr, TcpClientStack, TcpFullStack};
+use fugit::TimerInstantU32;
 use fugit_timer::Timer;
-use heapless::Vec;
+use heapless::{String, Vec};
 
 /// Unique socket for a network connection
 #[derive(Debug)]
@@ -49,11 +162,17 @@ pub struct Socket {
     /// Unique link id of AT
     #[allow(unused)]
     pub(crate) link_id: usize,
+
+    /// Local port if socket was bound by `bind()`. Bound sockets do not occupy a link id.
+    pub(crate) local_port: Option<u16>,
 }
 
 impl Socket {
     pub(crate) fn new(link_id: usize) -> Self {
-        Self { link_id }
+        Self {
+            link_id,
+            local_port: None,
+        }
     }
 }
 
@@ -68,6 +187,70 @@ pub(crate) enum SocketState {
     Connected,
     /// Socket was closed by URC message, but Socket object still exists and needs to be fully closed by calling 'close()'
     Closing,
+    /// Connection was established by a remote client and is waiting to be returned by 'accept()'
+    Incoming,
+}
+
+/// Transmission waiting for confirmation by ESP-AT
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct PendingSend<const TIMER_HZ: u32> {
+    /// Link of the transmission
+    pub(crate) link_id: usize,
+    /// Byte count of the transmission
+    pub(crate) length: usize,
+    /// Point in time the transmission times out. Based on `now()` of the timer, which needs to be monotonic,
+    /// as the timer may get (re)started by blocking operations while the transmission is pending.
+    pub(crate) deadline: TimerInstantU32<TIMER_HZ>,
+}
+
+/// TCP options of a socket, s. `set_socket_options()` of [Adapter]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SocketOptions {
+    /// Time in seconds a closed socket lingers for sending remaining data. None: disabled
+    pub linger: Option<u16>,
+
+    /// True disables Nagle's algorithm, so that small blocks are sent without delay
+    pub no_delay: bool,
+
+    /// Send timeout of ESP-AT in ms. 0: no timeout
+    pub send_timeout_ms: u32,
+
+    /// Interval of TCP keep-alive probes in seconds (1 - 7200) for detecting dead peers. None: disabled
+    pub keep_alive: Option<u16>,
+}
+
+/// Connection details of a single link as reported by ESP-AT
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionInfo {
+    /// Link ID
+    pub link_id: usize,
+
+    /// Connection type, e.g. TCP, UDP, SSL, etc.
+    pub protocol: String<8>,
+
+    /// Remote address
+    pub remote: SocketAddr,
+
+    /// Local port
+    pub local_port: u16,
+
+    /// True if the connection was established by a remote client (ESP-AT runs as server)
+    pub server: bool,
+}
+
+impl ConnectionInfo {
+    fn from_response(response: ConnectionStateResponse) -> Result<Self, Error> {
+        let ip = IpAddr::from_str(response.remote_ip.as_str())
+            .map_err(|_| Error::ConnectionStateError(AtError::InvalidResponse))?;
+
+        Ok(Self {
+            link_id: response.link_id,
+            protocol: response.connection_type,
+            remote: SocketAddr::new(ip, response.remote_port),
+            local_port: response.local_port,
+            server: response.tetype == 1,
+        })
+    }
 }
 
 /// Network related errors
@@ -76,12 +259,34 @@ pub enum Error {
     /// Error while sending CIPMUX command for enabling multiple connections
     EnablingMultiConnectionsFailed(AtError),
 
+    /// Error while sending CIPMUX command for enabling single connection mode
+    EnablingSingleConnectionFailed(AtError),
+
+    /// Error while sending CIPMODE command for switching the transmission mode
+    TransmissionModeError(AtError),
+
     /// Error while sending CIPRECVMODE command for enabling passive socket receiving mode
     EnablingPassiveSocketModeFailed(AtError),
 
+    /// Error while sending CIPRECVMODE command for enabling active socket receiving mode
+    #[cfg(feature = "active-mode")]
+    EnablingActiveSocketModeFailed(AtError),
+
+    /// Error while sending CIPDINFO command for enabling remote information of received data
+    EnablingDataInfoFailed(AtError),
+
     /// TCP connect command failed
     ConnectError(AtError),
 
+    /// Binding UDP socket to local port failed
+    BindError(AtError),
+
+    /// Error while configuring SNI, ALPN, PSK or authentication mode of a TLS connection
+    TlsConfigurationError(AtError),
+
+    /// Error while writing a certificate or private key (SYSMFG or SYSFLASH command)
+    CredentialWriteError(AtError),
+
     /// Preparing the transmission failed (CIPSEND command)
     TransmissionStartFailed(AtError),
 
@@ -94,6 +299,18 @@ pub enum Error {
     /// Socket close command failed
     CloseError(AtError),
 
+    /// Error while sending CIPSERVER or CIPSERVERMAXCONN command
+    ServerError(AtError),
+
+    /// Error while querying the connection state (CIPSTATE command)
+    ConnectionStateError(AtError),
+
+    /// Error while querying the length of received data (CIPRECVLEN command)
+    ReceiveLengthQueryFailed(AtError),
+
+    /// Error while setting the socket options (CIPTCPOPT command)
+    SocketOptionsError(AtError),
+
     /// AT-ESP confirmed receiving an unexpected byte count
     PartialSend,
 
@@ -112,6 +329,57 @@ pub enum Error {
     /// Socket was remotely closed and needs to either reconnected to fully closed by calling `close()` for [Adapter]
     ClosingSocket,
 
+    /// Datagram is larger then the TX_SIZE block size and can not be sent in one piece
+    DatagramTooLarge,
+
+    /// Socket needs to be bound to a local port by calling `bind()` first
+    UnboundSocket,
+
+    /// Socket is bound to a local port and may not be used like a connected client socket
+    BoundSocket,
+
+    /// Socket is bound, but `listen()` was not called yet
+    NotListening,
+
+    /// ESP-AT supports just a single TCP server, which is already running
+    ServerAlreadyRunning,
+
+    /// Server name is longer then 64 chars, more then 3 ALPN protocols are given, a protocol name or
+    /// PSK parameter is longer then 32 chars
+    InvalidTlsConfiguration,
+
+    /// Credential data is empty
+    InvalidCredential,
+
+    /// Keep-alive interval is not in the range of 1 to 7200 seconds
+    InvalidSocketOptions,
+
+    /// Received data of active receiving mode exceeded the buffer of the socket, so data was lost
+    #[cfg(feature = "active-mode")]
+    BufferOverflow,
+
+    /// UDP sockets are not supported in active receiving mode, as datagram boundaries are not preserved
+    #[cfg(feature = "active-mode")]
+    ActiveModeUnsupported,
+
+    /// Passthrough mode requires that no socket is open and no server is running
+    SocketsInUse,
+
+    /// Passthrough mode is active. Needs to be closed by `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// ESP-AT referenced a link ID exceeding the configured socket count, s. generic constant SOCKETS of [Adapter]
+    UnsupportedLinkId(usize),
+
+    /// IPv6 is not supported by the firmware, s. [firmware module](crate::firmware)
+    Ipv6Unsupported,
+
+    /// Querying the length of received data (CIPRECVLEN) is not supported by the firmware, s. [firmware module](crate::firmware)
+    ReceiveLengthQueryUnsupported,
+
     /// Received more data then requested from AT-ESP and data does not fit in (remaining) buffer.
     /// This indicates either a bug in this crate or in AT-ESP firmware.
     ReceiveOverflow,
@@ -124,19 +392,26 @@ pub enum Error {
     TimerError,
 }
 
-impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usize, const RX_SIZE: usize> TcpClientStack
-    for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE>
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > TcpClientStack for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
 {
     type TcpSocket = Socket;
     type Error = Error;
 
     /// Opens and returns a new socket
-    /// Currently only five parallel sockets are supported. If not socket is available [Error::NoSocketAvailable] is returned.
+    /// The number of parallel sockets is defined by the generic constant SOCKETS of [Adapter].
+    /// If not socket is available [Error::NoSocketAvailable] is returned.
     ///
     /// On first call ESP-AT is configured to support multiple connections.
     fn socket(&mut self) -> Result<Self::TcpSocket, Self::Error> {
         self.enable_multiple_connections()?;
-        self.open_socket()
+        Ok(Socket::new(self.open_link()?))
     }
 
     /// Opens a new TCP connection. Both IPv4 and IPv6 are supported.
@@ -146,33 +421,22 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
     /// is buffered on ESP-AT to a maximum size of around 8192 bytes.
     fn connect(&mut self, socket: &mut Socket, remote: SocketAddr) -> nb::Result<(), Self::Error> {
         self.process_urc_messages();
+        self.assert_no_pending_send()?;
+        Self::assert_socket_unbound(socket)?;
 
         if self.sockets[socket.link_id] == SocketState::Connected {
             return nb::Result::Err(nb::Error::Other(Error::AlreadyConnected));
         }
 
-        self.enable_passive_receiving_mode()?;
-        self.already_connected = false;
+        self.apply_socket_options(socket.link_id)?;
 
         let command = match remote {
             SocketAddr::V4(address) => ConnectCommand::tcp_v4(socket.link_id, address),
             SocketAddr::V6(address) => ConnectCommand::tcp_v6(socket.link_id, address),
         };
-        let result = self.send_command(command);
-        self.process_urc_messages();
+        let keep_alive = self.socket_options[socket.link_id].and_then(|options| options.keep_alive);
+        self.connect_link(socket.link_id, command.with_keep_alive(keep_alive), Some(remote))?;
 
-        // ESP-AT returned that given socket is already connected. This indicates that a URC Connect message was missed.
-        if self.already_connected {
-            self.sockets[socket.link_id] = SocketState::Connected;
-            return nb::Result::Ok(());
-        }
-        result?;
-
-        if self.sockets[socket.link_id] != SocketState::Connected {
-            return nb::Result::Err(nb::Error::Other(Error::UnconfirmedSocketState));
-        }
-
-        self.data_available[socket.link_id] = 0;
         nb::Result::Ok(())
     }
 
@@ -180,21 +444,49 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
     /// The current implementation never returns a Error.
     fn is_connected(&mut self, socket: &Self::TcpSocket) -> Result<bool, Self::Error> {
         self.process_urc_messages();
-        Ok(self.sockets[socket.link_id] == SocketState::Connected)
+        Ok(socket.local_port.is_none() && self.sockets[socket.link_id] == SocketState::Connected)
     }
 
     /// Sends the given buffer and returns the length (in bytes) sent.
     /// The data is divided into smaller blocks. The block size is determined by the generic constant TX_SIZE.
+    ///
+    /// If long transmission mode is enabled (s. `set_long_transmission()` of [Adapter]), the whole buffer is sent
+    /// by a single CIPSENDL command and just confirmed once.
+    ///
+    /// Sending does not block until ESP-AT confirms the transmission. If a block is still unconfirmed,
+    /// the length of all previously confirmed blocks is returned, or [nb::Error::WouldBlock] if there are none.
+    /// The pending block is resumed by the next call, which needs to start with the same data. The data itself is
+    /// not compared, the confirmed block just counts as sent. Returns [Error::PartialSend] if the buffer is shorter
+    /// than the pending block.
+    ///
+    /// While a block is pending, all other calls issuing commands (e.g. receiving, connecting or sending
+    /// to other sockets) return [nb::Error::WouldBlock] or a `SendPending` error.
     fn send(&mut self, socket: &mut Socket, buffer: &[u8]) -> nb::Result<usize, Error> {
         self.process_urc_messages();
-        self.assert_socket_connected(socket)?;
+        Self::assert_socket_unbound(socket)?;
+        self.assert_link_connected(socket.link_id)?;
 
-        for chunk in buffer.chunks(TX_SIZE) {
-            self.send_command(TransmissionPrepareCommand::new(socket.link_id, chunk.len()))?;
-            self.send_chunk(chunk)?;
+        let mut sent = self.poll_pending_send(socket.link_id, buffer.len())?.unwrap_or(0);
+
+        while sent < buffer.len() {
+            let remaining = &buffer[sent..];
+
+            if self.long_transmission && self.capabilities.long_transmission {
+                self.send_long(socket.link_id, remaining)?;
+            } else {
+                let chunk = &remaining[..remaining.len().min(TX_SIZE)];
+                self.send_command(TransmissionPrepareCommand::new(socket.link_id, chunk.len()))?;
+                self.send_chunk(socket.link_id, chunk)?;
+            }
+
+            match self.poll_send_confirmation() {
+                Ok(length) => sent += length,
+                Err(nb::Error::WouldBlock) if sent > 0 => return nb::Result::Ok(sent),
+                Err(error) => return Err(error),
+            }
         }
 
-        nb::Result::Ok(buffer.len())
+        nb::Result::Ok(sent)
     }
 
     /// Receives data (if available) and writes it to the given buffer.
@@ -203,8 +495,16 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
     /// In any case, data is read until the buffer is completely filled or no further data is available.
     fn receive(&mut self, socket: &mut Self::TcpSocket, buffer: &mut [u8]) -> nb::Result<usize, Self::Error> {
         self.process_urc_messages();
+        self.assert_link_ids_supported()?;
+        Self::assert_socket_unbound(socket)?;
 
-        if self.data_available[socket.link_id] == 0 {
+        #[cfg(feature = "active-mode")]
+        if self.active_mode {
+            return self.receive_buffered(socket.link_id, buffer);
+        }
+
+        self.assert_no_pending_send()?;
+        if !self.poll_data_available(socket.link_id)? {
             return nb::Result::Err(nb::Error::WouldBlock);
         }
 
@@ -232,79 +532,496 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
     /// If the socket has already been closed by the remote side or is not connected, no command
     /// is sent to the ESP-AT but only the internal status is set.
     /// In case of an error (which is returned) the socket is internally set to closed so that it is not lost and can be reused.
+    ///
+    /// Closing a listening socket stops the server. Already accepted connections are kept open.
     fn close(&mut self, socket: Self::TcpSocket) -> Result<(), Self::Error> {
+        if socket.local_port.is_some() {
+            return self.stop_server(&socket);
+        }
+
+        self.close_link(socket.link_id)
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > TcpFullStack for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Binds the socket to the given local port
+    ///
+    /// As link IDs of incoming connections are assigned by ESP-AT, the link ID of the socket is released.
+    /// Returns [Error::AlreadyConnected] if socket is already connected.
+    fn bind(&mut self, socket: &mut Self::TcpSocket, local_port: u16) -> Result<(), Self::Error> {
         self.process_urc_messages();
 
-        // Socket is not connected yet or was already closed remotely
-        if self.sockets[socket.link_id] == SocketState::Closing || self.sockets[socket.link_id] == SocketState::Open {
+        if socket.local_port.is_some() && self.server_port == socket.local_port {
+            return Err(Error::ServerAlreadyRunning);
+        }
+
+        if socket.local_port.is_none() {
+            if self.sockets[socket.link_id] == SocketState::Connected {
+                return Err(Error::AlreadyConnected);
+            }
+
             self.sockets[socket.link_id] = SocketState::Closed;
+        }
+
+        socket.local_port = Some(local_port);
+        Ok(())
+    }
+
+    /// Starts the TCP server on the bound port
+    ///
+    /// ESP-AT supports just a single server, otherwise [Error::ServerAlreadyRunning] is returned.
+    /// The max. number of clients is defined by `set_server_max_connections()`.
+    fn listen(&mut self, socket: &mut Self::TcpSocket) -> Result<(), Self::Error> {
+        let port = socket.local_port.ok_or(Error::UnboundSocket)?;
+
+        if self.server_port.is_some() {
+            return Err(Error::ServerAlreadyRunning);
+        }
+
+        self.configure_receiving_mode()?;
+        self.send_command(ServerMaxConnectionsCommand::new(self.server_max_connections))?;
+        self.send_command(ServerCommand::start(port))?;
+
+        self.server_port = Some(port);
+        Ok(())
+    }
+
+    /// Returns the next incoming client connection, which was signaled by URC message
+    ///
+    /// Remote address is queried by CIPSTATE command.
+    fn accept(&mut self, socket: &mut Self::TcpSocket) -> nb::Result<(Self::TcpSocket, SocketAddr), Self::Error> {
+        self.process_urc_messages();
+        self.assert_link_ids_supported()?;
+
+        if socket.local_port.is_none() {
+            return nb::Result::Err(nb::Error::Other(Error::UnboundSocket));
+        }
+
+        if self.server_port != socket.local_port {
+            return nb::Result::Err(nb::Error::Other(Error::NotListening));
+        }
+
+        let link_id = match self.sockets.iter().position(|state| state == &SocketState::Incoming) {
+            None => return nb::Result::Err(nb::Error::WouldBlock),
+            Some(link_id) => link_id,
+        };
+
+        self.assert_no_pending_send()?;
+        let remote = match self.query_remote(link_id)? {
+            None => {
+                // Connection was closed in the meantime
+                self.sockets[link_id] = SocketState::Closed;
+                return nb::Result::Err(nb::Error::WouldBlock);
+            }
+            Some(remote) => remote,
+        };
+
+        self.sockets[link_id] = SocketState::Connected;
+        self.remotes[link_id] = Some(remote);
+        nb::Result::Ok((Socket::new(link_id), remote))
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Sends the connect command for the given link and waits for the URC confirmation
+    ///
+    /// On first call ESP-AT is configured for passive socket receiving mode.
+    pub(crate) fn connect_link<Cmd: AtatCmd<LEN> + CommandErrorHandler<Error = Error>, const LEN: usize>(
+        &mut self,
+        link_id: usize,
+        command: Cmd,
+        remote: Option<SocketAddr>,
+    ) -> Result<(), Error> {
+        if let Some(remote) = &remote {
+            self.assert_remote_supported(remote)?;
+        }
+
+        self.configure_receiving_mode()?;
+        self.reset_received_data(link_id);
+        self.already_connected = false;
+        self.connecting[link_id] = true;
+
+        let result = self.send_command(command);
+        self.process_urc_messages();
+
+        // ESP-AT returned that given socket is already connected. This indicates that a URC Connect message was missed.
+        if self.already_connected {
+            self.sockets[link_id] = SocketState::Connected;
+            self.connecting[link_id] = false;
+            self.remotes[link_id] = remote;
             return Ok(());
         }
 
-        let mut result = self.send_command(CloseSocketCommand::new(socket.link_id));
+        // Connection may still get established by ESP-AT, if the command timed out
+        if let Err(error) = result {
+            self.connecting[link_id] = error == Error::ConnectError(AtError::Timeout);
+            return Err(error);
+        }
+
+        // Connect message may have been missed, so the connection gets recovered by `resync_connections()`
+        if self.sockets[link_id] != SocketState::Connected {
+            return Err(Error::UnconfirmedSocketState);
+        }
+
+        self.connecting[link_id] = false;
+        self.data_available[link_id] = 0;
+        self.remotes[link_id] = remote;
+        Ok(())
+    }
+
+    /// Closes the given link
+    ///
+    /// If the link has already been closed by the remote side or is not connected, no command
+    /// is sent to the ESP-AT but only the internal status is set.
+    /// In case of an error (which is returned) the link is internally set to closed so that it is not lost and can be reused.
+    pub(crate) fn close_link(&mut self, link_id: usize) -> Result<(), Error> {
         self.process_urc_messages();
 
-        if self.sockets[socket.link_id] != SocketState::Closing && result.is_ok() {
+        // Unconfirmed transmission is discarded, so that other sockets are not blocked
+        if self.pending_send.map(|pending| pending.link_id) == Some(link_id) {
+            self.pending_send = None;
+        }
+
+        self.connecting[link_id] = false;
+
+        // Socket is not connected yet or was already closed remotely
+        if self.sockets[link_id] == SocketState::Closing || self.sockets[link_id] == SocketState::Open {
+            self.sockets[link_id] = SocketState::Closed;
+            return Ok(());
+        }
+
+        let mut result = self.send_command(CloseSocketCommand::new(link_id));
+        self.process_urc_messages();
+
+        if self.sockets[link_id] != SocketState::Closing && result.is_ok() {
             result = Err(Error::UnconfirmedSocketState);
         }
 
         // Setting to Closed even on error. Otherwise socket can not be reused in future, as its consumed.
-        self.sockets[socket.link_id] = SocketState::Closed;
+        self.sockets[link_id] = SocketState::Closed;
 
         result?;
         Ok(())
     }
-}
 
-impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usize, const RX_SIZE: usize>
-    Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE>
-{
-    /// Sends a chunk of max. 256 bytes
-    fn send_chunk(&mut self, data: &[u8]) -> Result<(), Error> {
+    /// Stops the server if the given socket is listening.
+    /// Pending connections, which were not accepted yet, get closed.
+    fn stop_server(&mut self, socket: &Socket) -> Result<(), Error> {
+        if self.server_port.is_none() || self.server_port != socket.local_port {
+            return Ok(());
+        }
+
+        self.send_command(ServerCommand::stop())?;
+        self.server_port = None;
+
+        for link_id in 0..self.sockets.len() {
+            if self.sockets[link_id] == SocketState::Incoming {
+                self.sockets[link_id] = SocketState::Connected;
+                let _ = self.close_link(link_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queries the connection details of all links and reconciles the cached socket states, which may drift
+    /// from the actual state of ESP-AT after a missed URC message:
+    /// * Connected sockets unknown to ESP-AT are marked as closed and need to be closed by calling `close()`.
+    /// * Incoming connections unknown to ESP-AT are discarded.
+    /// * Connections to the running server, which were not signaled yet, get returned by `accept()`.
+    /// * Sockets known to ESP-AT, whose connect message was missed after calling `connect()`, are marked as connected.
+    /// * Other connections known to ESP-AT are left untouched, e.g. if the link id is used by an unconnected socket.
+    ///
+    /// All connections are parsed before any socket state is updated, so an invalid response leaves the states
+    /// untouched.
+    ///
+    /// Uses CIPSTATE command or CIPSTATUS on older firmware versions (s. [Capabilities](crate::firmware::Capabilities)).
+    pub fn resync_connections(&mut self) -> Result<Vec<ConnectionInfo, SOCKETS>, Error> {
+        self.process_urc_messages();
+
+        if self.passthrough_active {
+            return Err(Error::PassthroughActive);
+        }
+
+        let mut connections: Vec<ConnectionInfo, SOCKETS> = Vec::new();
+        for response in self.query_connection_states()? {
+            if response.link_id >= SOCKETS {
+                continue;
+            }
+
+            // Link IDs are unique and below SOCKETS, so capacity is sufficient
+            let _ = connections.push(ConnectionInfo::from_response(response)?);
+        }
+
+        let mut listed = [false; SOCKETS];
+        for info in connections.iter() {
+            listed[info.link_id] = true;
+
+            match self.sockets[info.link_id] {
+                SocketState::Closed if info.server && self.server_port.is_some() => {
+                    self.sockets[info.link_id] = SocketState::Incoming;
+                    self.reset_received_data(info.link_id);
+                }
+                SocketState::Open | SocketState::Closing if self.connecting[info.link_id] => {
+                    self.sockets[info.link_id] = SocketState::Connected;
+                    self.connecting[info.link_id] = false;
+                    self.remotes[info.link_id] = Some(info.remote);
+                }
+                _ => {}
+            }
+        }
+
+        for (link_id, state) in self.sockets.iter_mut().enumerate() {
+            if listed[link_id] {
+                continue;
+            }
+
+            match state {
+                SocketState::Connected => *state = SocketState::Closing,
+                SocketState::Incoming => *state = SocketState::Closed,
+                _ => {}
+            }
+        }
+
+        Ok(connections)
+    }
+
+    /// Queries the remote address of the given link.
+    /// Returns None if ESP-AT has no connection with the given link id.
+    fn query_remote(&mut self, link_id: usize) -> Result<Option<SocketAddr>, Error> {
+        for response in self.query_connection_states()? {
+            if response.link_id == link_id {
+                return Ok(Some(ConnectionInfo::from_response(response)?.remote));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Sets the TCP options of the given socket. The options are applied when connecting the socket by
+    /// `connect()` or `connect_tls()`, so they need to be set before. Returns [Error::AlreadyConnected]
+    /// if the socket is already connected.
+    ///
+    /// Linger, Nagle and send timeout are set by CIPTCPOPT command, keep-alive by CIPSTART command.
+    pub fn set_socket_options(&mut self, socket: &Socket, options: SocketOptions) -> Result<(), Error> {
+        self.process_urc_messages();
+        Self::assert_socket_unbound(socket)?;
+
+        if self.sockets[socket.link_id] == SocketState::Connected {
+            return Err(Error::AlreadyConnected);
+        }
+
+        if matches!(options.keep_alive, Some(interval) if interval == 0 || interval > 7200) {
+            return Err(Error::InvalidSocketOptions);
+        }
+
+        self.socket_options[socket.link_id] = Some(options);
+        Ok(())
+    }
+
+    /// Sends the CIPTCPOPT command if socket options were set for the given link
+    pub(crate) fn apply_socket_options(&mut self, link_id: usize) -> Result<(), Error> {
+        let options = match self.socket_options[link_id] {
+            None => return Ok(()),
+            Some(options) => options,
+        };
+
+        let linger = options.linger.map(i32::from).unwrap_or(-1);
+        self.send_command(SocketOptionsCommand::new(
+            link_id,
+            linger,
+            options.no_delay,
+            options.send_timeout_ms,
+        ))?;
+        Ok(())
+    }
+
+    /// Queries the data length buffered by ESP-AT for all links (CIPRECVLEN command) and updates the available
+    /// data accordingly. Returns the available data length per link, index = link_id.
+    ///
+    /// Usually the available data is signaled by +IPD messages. Querying is required just if such a message
+    /// was missed, s. `set_receive_length_polling()` of [Adapter] for polling automatically.
+    pub fn query_receive_lengths(&mut self) -> Result<[usize; SOCKETS], Error> {
+        self.process_urc_messages();
+
+        if !self.capabilities.receive_length_query {
+            return Err(Error::ReceiveLengthQueryUnsupported);
+        }
+
+        let response = self.send_command(ReceiveLengthQueryCommand::new())?;
+        for (link_id, length) in response.lengths.into_iter().enumerate().take(SOCKETS) {
+            self.data_available[link_id] = length;
+        }
+
+        self.receive_poll_deadlines = [None; SOCKETS];
+        Ok(self.data_available)
+    }
+
+    /// Returns true if data is available for the given link. If polling is enabled, the receive lengths are
+    /// queried once no data was available for the configured interval.
+    pub(crate) fn poll_data_available(&mut self, link_id: usize) -> Result<bool, Error> {
+        if self.data_available[link_id] > 0 {
+            self.receive_poll_deadlines[link_id] = None;
+            return Ok(true);
+        }
+
+        let interval = match self.receive_length_polling {
+            Some(interval) if self.capabilities.receive_length_query => interval,
+            _ => return Ok(false),
+        };
+
+        let now = self.timer.now();
+        let deadline = *self.receive_poll_deadlines[link_id].get_or_insert(now + interval);
+        if now < deadline {
+            return Ok(false);
+        }
+
+        self.query_receive_lengths()?;
+        Ok(self.data_available[link_id] > 0)
+    }
+
+    /// Queries the state of all connections by CIPSTATE or CIPSTATUS command, depending on the firmware capabilities
+    fn query_connection_states(&mut self) -> Result<Vec<ConnectionStateResponse, MAX_LINKS>, Error> {
+        if self.capabilities.connection_state_query {
+            return self.send_command(ConnectionStateCommand::new());
+        }
+
+        self.send_command(ConnectionStatusCommand::new())
+    }
+
+    /// Sends a chunk of max. TX_SIZE bytes. The transmission is pending until confirmed by ESP-AT.
+    pub(crate) fn send_chunk(&mut self, link_id: usize, data: &[u8]) -> Result<(), Error> {
         self.send_confirmed = None;
         self.recv_byte_count = None;
 
         self.send_command::<TransmissionCommand<'_>, TX_SIZE>(TransmissionCommand::new(data))?;
-        self.timer.start(self.send_timeout).map_err(|_| Error::TimerError)?;
+        self.start_pending_send(link_id, data.len());
+        Ok(())
+    }
 
-        while self.send_confirmed.is_none() {
-            self.process_urc_messages();
+    /// Sends the whole buffer by a single CIPSENDL command. Just the complete transmission gets confirmed.
+    pub(crate) fn send_long(&mut self, link_id: usize, buffer: &[u8]) -> Result<(), Error> {
+        self.send_confirmed = None;
+        self.recv_byte_count = None;
+        self.send_progress = None;
 
-            if let Some(send_success) = self.send_confirmed {
-                // Transmission failed
-                if !send_success {
-                    // Reset prompt status. Otherwise client does not match any command responses.
-                    self.client.reset();
-                    return Err(Error::SendFailed(AtError::Error));
-                }
+        self.send_command(LongTransmissionPrepareCommand::new(link_id, buffer.len()))?;
+        for chunk in buffer.chunks(TX_SIZE) {
+            self.send_command::<TransmissionCommand<'_>, TX_SIZE>(TransmissionCommand::new(chunk))?;
+        }
 
-                // Byte count does not match
-                if self.recv_byte_count.is_some() && *self.recv_byte_count.as_ref().unwrap() != data.len() {
-                    return Err(Error::PartialSend);
-                }
+        self.start_pending_send(link_id, buffer.len());
+        Ok(())
+    }
 
-                return Ok(());
+    /// Marks the transmission as pending and sets its timeout deadline
+    fn start_pending_send(&mut self, link_id: usize, length: usize) {
+        let deadline = self.timer.now() + self.send_timeout;
+        self.pending_send = Some(PendingSend {
+            link_id,
+            length,
+            deadline,
+        });
+    }
+
+    /// Resumes the pending transmission of the given link. The resuming buffer of the given length needs to cover
+    /// the pending block, otherwise [Error::PartialSend] is returned and the transmission is discarded.
+    /// Returns None if no transmission is pending and [nb::Error::WouldBlock] if a transmission of another link is pending.
+    pub(crate) fn poll_pending_send(&mut self, link_id: usize, length: usize) -> nb::Result<Option<usize>, Error> {
+        match self.pending_send {
+            None => nb::Result::Ok(None),
+            Some(pending) if pending.link_id != link_id => nb::Result::Err(nb::Error::WouldBlock),
+            Some(pending) if pending.length > length => {
+                self.pending_send = None;
+                nb::Result::Err(nb::Error::Other(Error::PartialSend))
             }
+            Some(_) => self.poll_send_confirmation().map(Some),
+        }
+    }
 
-            match self.timer.wait() {
-                Ok(_) => {
-                    // Reset prompt status. Otherwise client does not match any command responses.
-                    self.client.reset();
-                    return Err(Error::SendFailed(AtError::Timeout));
-                }
-                Err(error) => match error {
-                    nb::Error::Other(_) => return Err(Error::TimerError),
-                    nb::Error::WouldBlock => {}
-                },
+    /// Returns [nb::Error::WouldBlock] while a transmission is unconfirmed, as the responses of further commands
+    /// would interleave with its confirmation. The transmission gets completed by calling `send()` again.
+    pub(crate) fn assert_no_pending_send(&self) -> nb::Result<(), Error> {
+        if self.pending_send.is_some() {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        nb::Result::Ok(())
+    }
+
+    /// Checks if the pending transmission got confirmed and returns its length.
+    /// The timeout gets restarted by progress messages of long transmissions.
+    pub(crate) fn poll_send_confirmation(&mut self) -> nb::Result<usize, Error> {
+        let pending = match self.pending_send {
+            None => return nb::Result::Ok(0),
+            Some(pending) => pending,
+        };
+
+        self.process_urc_messages();
+
+        if let Some(send_success) = self.send_confirmed {
+            self.pending_send = None;
+
+            // Transmission failed
+            if !send_success {
+                // Reset prompt status. Otherwise client does not match any command responses.
+                self.client.reset();
+                return nb::Result::Err(nb::Error::Other(Error::SendFailed(AtError::Error)));
+            }
+
+            // Byte count does not match
+            if self.recv_byte_count.is_some() && *self.recv_byte_count.as_ref().unwrap() != pending.length {
+                return nb::Result::Err(nb::Error::Other(Error::PartialSend));
             }
+
+            return nb::Result::Ok(pending.length);
         }
 
-        Ok(())
+        let now = self.timer.now();
+
+        // Long transmission is still in progress
+        if self.send_progress.take().is_some() {
+            self.pending_send = Some(PendingSend {
+                deadline: now + self.send_timeout,
+                ..pending
+            });
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        if now < pending.deadline {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        self.pending_send = None;
+
+        // Reset prompt status. Otherwise client does not match any command responses.
+        self.client.reset();
+        nb::Result::Err(nb::Error::Other(Error::SendFailed(AtError::Timeout)))
     }
 
     /// Enables multiple connections.
     /// Stores internal state, so command is just sent once for saving bandwidth
-    fn enable_multiple_connections(&mut self) -> Result<(), Error> {
+    pub(crate) fn enable_multiple_connections(&mut self) -> Result<(), Error> {
+        if self.passthrough_active {
+            return Err(Error::PassthroughActive);
+        }
+
         if self.multi_connections_enabled {
             return Ok(());
         }
@@ -314,43 +1031,103 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
         Ok(())
     }
 
-    /// Enables the passive socket receiving mode
+    /// Enables the passive socket receiving mode, or the active mode if enabled
     /// Stores internal state, so command is just sent once for saving bandwidth
-    fn enable_passive_receiving_mode(&mut self) -> Result<(), Error> {
-        if self.passive_mode_enabled {
+    pub(crate) fn configure_receiving_mode(&mut self) -> Result<(), Error> {
+        if self.receiving_mode_configured {
+            return Ok(());
+        }
+
+        #[cfg(feature = "active-mode")]
+        if self.active_mode {
+            self.send_command(SetSocketReceivingModeCommand::active_mode())?;
+            self.receiving_mode_configured = true;
             return Ok(());
         }
 
         self.send_command(SetSocketReceivingModeCommand::passive_mode())?;
-        self.passive_mode_enabled = true;
+        self.receiving_mode_configured = true;
+        Ok(())
+    }
+
+    /// Enables the remote information (IP + port) of received data
+    /// Stores internal state, so command is just sent once for saving bandwidth
+    pub(crate) fn enable_data_info(&mut self) -> Result<(), Error> {
+        if self.data_info_enabled {
+            return Ok(());
+        }
+
+        self.send_command(SetDataInfoCommand::enabled())?;
+        self.data_info_enabled = true;
         Ok(())
     }
 
     /// Assigns a free link_id. Returns an error in case no more free sockets are available
-    fn open_socket(&mut self) -> Result<Socket, Error> {
+    pub(crate) fn open_link(&mut self) -> Result<usize, Error> {
         if let Some(link_id) = self.sockets.iter().position(|state| state == &SocketState::Closed) {
             self.sockets[link_id] = SocketState::Open;
-            return Ok(Socket::new(link_id));
+            self.remotes[link_id] = None;
+            self.connecting[link_id] = false;
+            self.socket_options[link_id] = None;
+            return Ok(link_id);
         }
 
         Err(Error::NoSocketAvailable)
     }
 
-    /// Asserts that the given socket is connected and returns otherwise the appropriate error
-    fn assert_socket_connected(&self, socket: &Socket) -> nb::Result<(), Error> {
-        if self.sockets[socket.link_id] == SocketState::Closing {
+    /// Asserts that the given socket is not bound for listening
+    pub(crate) fn assert_socket_unbound(socket: &Socket) -> Result<(), Error> {
+        if socket.local_port.is_some() {
+            return Err(Error::BoundSocket);
+        }
+
+        Ok(())
+    }
+
+    /// Asserts that the firmware supports the address family of the given remote
+    pub(crate) fn assert_remote_supported(&self, remote: &SocketAddr) -> Result<(), Error> {
+        if remote.is_ipv6() && !self.capabilities.ipv6 {
+            return Err(Error::Ipv6Unsupported);
+        }
+
+        Ok(())
+    }
+
+    /// Returns an error once if ESP-AT referenced a link ID exceeding the configured socket count
+    pub(crate) fn assert_link_ids_supported(&mut self) -> Result<(), Error> {
+        match self.unsupported_link_id.take() {
+            None => Ok(()),
+            Some(link_id) => Err(Error::UnsupportedLinkId(link_id)),
+        }
+    }
+
+    /// Asserts that the given link is connected and returns otherwise the appropriate error
+    pub(crate) fn assert_link_connected(&self, link_id: usize) -> nb::Result<(), Error> {
+        if self.sockets[link_id] == SocketState::Closing {
             return nb::Result::Err(nb::Error::Other(Error::ClosingSocket));
         }
 
-        if self.sockets[socket.link_id] != SocketState::Connected {
+        if self.sockets[link_id] != SocketState::Connected {
             return nb::Result::Err(nb::Error::Other(Error::SocketUnconnected));
         }
 
         nb::Result::Ok(())
     }
 
+    /// Discards any received data of the given link, e.g. when the link gets reused
+    pub(crate) fn reset_received_data(&mut self, link_id: usize) {
+        self.data_available[link_id] = 0;
+        self.receive_poll_deadlines[link_id] = None;
+
+        #[cfg(feature = "active-mode")]
+        {
+            self.buffers[link_id].clear();
+            self.buffer_overflow[link_id] = false;
+        }
+    }
+
     /// Reduces the available data length mark by the given length
-    fn reduce_data_available(&mut self, link_id: usize, length: usize) {
+    pub(crate) fn reduce_data_available(&mut self, link_id: usize, length: usize) {
         if self.data_available[link_id] < length {
             self.data_available[link_id] = 0;
             return;
@@ -0,0 +1,275 @@
+use crate::stack::{Error, Socket};
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack, UdpClientStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 8>;
+
+#[test]
+fn test_connect_enables_active_mode() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    connect_socket(&mut adapter);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("AT+CIPRECVMODE=0\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_connect_active_mode_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+
+    let mut socket = TcpClientStack::socket(&mut adapter).unwrap();
+    let error = TcpClientStack::connect(
+        &mut adapter,
+        &mut socket,
+        SocketAddr::from_str("127.0.0.1:5000").unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        nb::Error::Other(Error::EnablingActiveSocketModeFailed(AtError::Parse)),
+        error
+    );
+}
+
+#[test]
+fn test_receive_buffered_data() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_urc_message(b"+IPD,0,4:abcd");
+
+    let mut buffer = [b' '; 16];
+    assert_eq!(
+        4,
+        TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap()
+    );
+    assert_eq!(b"abcd", &buffer[..4]);
+
+    // Data is not requested by an additional command
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_buffered_data_multiple_messages() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,3:abc");
+    adapter.client.add_urc_message(b"+IPD,0,2:de");
+
+    let mut buffer = [b' '; 4];
+    assert_eq!(
+        4,
+        TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap()
+    );
+    assert_eq!(b"abcd", &buffer);
+
+    assert_eq!(
+        1,
+        TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap()
+    );
+    assert_eq!(b'e', buffer[0]);
+}
+
+#[test]
+fn test_receive_buffered_data_remote_info() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4,\"10.0.0.1\",5000:abcd");
+
+    let mut buffer = [b' '; 16];
+    assert_eq!(
+        4,
+        TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap()
+    );
+    assert_eq!(b"abcd", &buffer[..4]);
+}
+
+#[test]
+fn test_receive_buffered_data_other_socket() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,3,4:abcd");
+
+    let mut buffer = [b' '; 16];
+    let error = TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+    assert_eq!(4, adapter.buffers[3].len());
+}
+
+#[test]
+fn test_receive_buffered_data_after_remote_close() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4:abcd");
+    adapter.client.add_urc_message(b"0,CLOSED\r\n");
+
+    let mut buffer = [b' '; 16];
+    assert_eq!(
+        4,
+        TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap()
+    );
+    assert!(!adapter.is_connected(&socket).unwrap());
+}
+
+#[test]
+fn test_receive_buffer_overflow() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,6:abcdef");
+    adapter.client.add_urc_message(b"+IPD,0,6:ghijkl");
+
+    let mut buffer = [b' '; 16];
+    let error = TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::BufferOverflow), error);
+
+    // Buffered data is kept
+    assert_eq!(
+        8,
+        TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap()
+    );
+    assert_eq!(b"abcdefgh", &buffer[..8]);
+}
+
+#[test]
+fn test_receive_buffer_overflow_multiple_links() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.buffer_data(0, b"abcdefghij");
+    adapter.buffer_data(1, b"abcdefghij");
+
+    let mut buffer = [b' '; 16];
+    let error = TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::BufferOverflow), error);
+
+    // Overflow of other link is kept
+    assert_eq!([false, true, false, false, false], adapter.buffer_overflow);
+}
+
+#[test]
+fn test_enable_after_passive_mode_configured() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_closed();
+    TcpClientStack::close(&mut adapter, socket).unwrap();
+
+    adapter.enable_active_receiving_mode();
+    adapter.client.reset_captured_commands();
+    connect_socket_configured(&mut adapter);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPRECVMODE=0\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_restart_discards_buffered_data() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    connect_socket(&mut adapter);
+
+    adapter.buffer_data(0, b"abcdefghij");
+    adapter.client.add_urc_ready();
+    adapter.process_urc_messages();
+
+    assert!(adapter.buffers[0].is_empty());
+    assert!(!adapter.buffer_overflow[0]);
+}
+
+#[test]
+fn test_connect_discards_buffered_data() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+    adapter.buffer_data(0, b"old");
+
+    let mut socket = connect_socket(&mut adapter);
+
+    let mut buffer = [b' '; 16];
+    let error = TcpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
+#[test]
+fn test_udp_not_supported() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.enable_active_receiving_mode();
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    let error =
+        UdpClientStack::connect(&mut adapter, &mut socket, SocketAddr::from_str("10.0.0.1:53").unwrap()).unwrap_err();
+    assert_eq!(Error::ActiveModeUnsupported, error);
+}
+
+fn connect_socket(adapter: &mut AdapterType) -> Socket {
+    // Receiving socket
+    adapter.client.add_ok_response();
+
+    connect_socket_configured(adapter)
+}
+
+/// Helper for connecting a socket, when multiple connections are already enabled
+fn connect_socket_configured(adapter: &mut AdapterType) -> Socket {
+    // Receiving mode + connect command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = TcpClientStack::socket(adapter).unwrap();
+    TcpClientStack::connect(adapter, &mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap()).unwrap();
+
+    socket
+}
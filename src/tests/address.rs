@@ -1,8 +1,10 @@
 use crate::tests::mock::{MockAtatClient, MockTimer};
 use crate::wifi::WifiAdapter;
-use crate::wifi::{Adapter, AddressErrors};
+use crate::wifi::{Adapter, AddressErrors, IpConfig, IpConfigError};
 use alloc::string::ToString;
 use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::Ipv4Addr;
 type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 64>;
 
 #[test]
@@ -161,3 +163,99 @@ fn test_command_error() {
         adapter.get_address().unwrap_err()
     );
 }
+
+#[test]
+fn test_set_static_ip() {
+    let client = MockAtatClient::new();
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.add_ok_response();
+
+    adapter
+        .set_static_ip(&IpConfig {
+            ip: Ipv4Addr::from_str("192.168.1.5").unwrap(),
+            gateway: Ipv4Addr::from_str("192.168.1.1").unwrap(),
+            netmask: Ipv4Addr::from_str("255.255.255.0").unwrap(),
+        })
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!(
+        "AT+CIPSTA=\"192.168.1.5\",\"192.168.1.1\",\"255.255.255.0\"\r\n".to_string(),
+        commands[0]
+    );
+}
+
+#[test]
+fn test_set_static_ip_command_error() {
+    let client = MockAtatClient::new();
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.add_error_response();
+
+    let error = adapter
+        .set_static_ip(&IpConfig {
+            ip: Ipv4Addr::from_str("192.168.1.5").unwrap(),
+            gateway: Ipv4Addr::from_str("192.168.1.1").unwrap(),
+            netmask: Ipv4Addr::from_str("255.255.255.0").unwrap(),
+        })
+        .unwrap_err();
+    assert_eq!(IpConfigError::ConfigurationError(AtError::Parse), error);
+}
+
+#[test]
+fn test_set_dhcp() {
+    let client = MockAtatClient::new();
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.set_dhcp(true).unwrap();
+    adapter.set_dhcp(false).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CWDHCP=1,1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CWDHCP=0,1\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_set_dhcp_command_error() {
+    let client = MockAtatClient::new();
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.add_error_response();
+
+    let error = adapter.set_dhcp(true).unwrap_err();
+    assert_eq!(IpConfigError::DhcpError(AtError::Parse), error);
+}
+
+#[test]
+fn test_get_ip_config() {
+    let client = MockAtatClient::new();
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.add_response(b"+CIPSTA:ip:\"192.168.1.5\"\r\n+CIPSTA:gateway:\"192.168.1.1\"\r\n+CIPSTA:netmask:\"255.255.255.0\"\r\n+CIPSTA:ip6ll:\"fe80::e6ee:e64e:84c:a745\"\r\n");
+
+    let config = adapter.get_ip_config().unwrap();
+    assert_eq!(Ipv4Addr::from_str("192.168.1.5").unwrap(), config.ip);
+    assert_eq!(Ipv4Addr::from_str("192.168.1.1").unwrap(), config.gateway);
+    assert_eq!(Ipv4Addr::from_str("255.255.255.0").unwrap(), config.netmask);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPSTA?\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_get_ip_config_parse_error() {
+    let client = MockAtatClient::new();
+    let timer = MockTimer::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.add_response(b"+CIPSTA:ip:\"192.168.1\"\r\n");
+
+    let error = adapter.get_ip_config().unwrap_err();
+    assert_eq!(IpConfigError::ConfigurationError(AtError::Parse), error);
+}
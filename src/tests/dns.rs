@@ -0,0 +1,294 @@
+use crate::dns::DnsError;
+use crate::stack::PendingSend;
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{AddrType, Dns, IpAddr};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 256>;
+
+#[test]
+fn test_get_host_by_name_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:\"10.0.0.1\"\r\n");
+    client.add_response(b"+CIPDOMAIN:\"10.0.0.1\"\r\n");
+    client.add_response(b"+CIPDOMAIN:\"2001:db8::1\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.get_host_by_name("example.org", AddrType::Either).unwrap();
+    adapter.get_host_by_name("example.org", AddrType::IPv4).unwrap();
+    adapter.get_host_by_name("example.org", AddrType::IPv6).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("AT+CIPDOMAIN=\"example.org\",1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPDOMAIN=\"example.org\",2\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPDOMAIN=\"example.org\",3\r\n".to_string(), commands[2]);
+}
+
+#[test]
+fn test_get_host_by_name_ipv4() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:\"93.184.216.34\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::IPv4).unwrap();
+    assert_eq!(IpAddr::from_str("93.184.216.34").unwrap(), address);
+}
+
+#[test]
+fn test_get_host_by_name_ipv6() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:\"2606:2800:220:1:248:1893:25c8:1946\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::IPv6).unwrap();
+    assert_eq!(IpAddr::from_str("2606:2800:220:1:248:1893:25c8:1946").unwrap(), address);
+}
+
+#[test]
+fn test_get_host_by_name_send_pending() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.pending_send = Some(PendingSend {
+        link_id: 0,
+        length: 4,
+        deadline: MockTimer::instant_ms(5_000),
+    });
+
+    let error = adapter.get_host_by_name("example.org", AddrType::Either).unwrap_err();
+    assert_eq!(nb::Error::Other(DnsError::SendPending), error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_get_host_by_name_unquoted_address() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:93.184.216.34\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::Either).unwrap();
+    assert_eq!(IpAddr::from_str("93.184.216.34").unwrap(), address);
+}
+
+#[test]
+fn test_get_host_by_name_either_ipv6_fallback() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:\"2001:db8::1\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let address = adapter.get_host_by_name("example.org", AddrType::Either).unwrap();
+    assert_eq!(IpAddr::from_str("2001:db8::1").unwrap(), address);
+}
+
+#[test]
+fn test_get_host_by_name_address_type_mismatch() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:\"2001:db8::1\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.get_host_by_name("example.org", AddrType::IPv4).unwrap_err();
+    assert_eq!(nb::Error::Other(DnsError::AddressTypeMismatch), error);
+}
+
+#[test]
+fn test_get_host_by_name_invalid_address() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDOMAIN:\"not an address\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.get_host_by_name("example.org", AddrType::Either).unwrap_err();
+    assert_eq!(nb::Error::Other(DnsError::AddressParseError), error);
+}
+
+#[test]
+fn test_get_host_by_name_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.get_host_by_name("unknown.example.org", AddrType::Either).unwrap_err();
+    assert_eq!(nb::Error::Other(DnsError::ResolveError(AtError::Parse)), error);
+}
+
+#[test]
+fn test_get_host_by_name_invalid_hostname_length() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let hostname = "a".repeat(65);
+    let error = adapter.get_host_by_name(&hostname, AddrType::Either).unwrap_err();
+    assert_eq!(nb::Error::Other(DnsError::InvalidHostnameLength), error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_get_host_by_address_unsupported() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.get_host_by_address(IpAddr::from_str("10.0.0.1").unwrap()).unwrap_err();
+    assert_eq!(nb::Error::Other(DnsError::Unsupported), error);
+}
+
+#[test]
+fn test_set_dns_servers_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_dns_servers(&[IpAddr::from_str("208.67.222.222").unwrap()]).unwrap();
+    adapter
+        .set_dns_servers(&[
+            IpAddr::from_str("1.1.1.1").unwrap(),
+            IpAddr::from_str("8.8.8.8").unwrap(),
+            IpAddr::from_str("2001:db8::1").unwrap(),
+        ])
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPDNS=1,\"208.67.222.222\"\r\n".to_string(), commands[0]);
+    assert_eq!(
+        "AT+CIPDNS=1,\"1.1.1.1\",\"8.8.8.8\",\"2001:0db8:0:0:0:0:0:0001\"\r\n".to_string(),
+        commands[1]
+    );
+}
+
+#[test]
+fn test_set_dns_servers_legacy_firmware() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.capabilities.temporary_dns_configuration = true;
+
+    adapter.set_dns_servers(&[IpAddr::from_str("1.1.1.1").unwrap()]).unwrap();
+    adapter.reset_dns_servers().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPDNS_CUR=1,\"1.1.1.1\"\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPDNS_CUR=0\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_set_dns_servers_invalid_count() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let server = IpAddr::from_str("1.1.1.1").unwrap();
+
+    assert_eq!(DnsError::InvalidServerCount, adapter.set_dns_servers(&[]).unwrap_err());
+    assert_eq!(
+        DnsError::InvalidServerCount,
+        adapter.set_dns_servers(&[server, server, server, server]).unwrap_err()
+    );
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_set_dns_servers_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.set_dns_servers(&[IpAddr::from_str("1.1.1.1").unwrap()]).unwrap_err();
+    assert_eq!(DnsError::ConfigurationError(AtError::Parse), error);
+}
+
+#[test]
+fn test_reset_dns_servers_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.reset_dns_servers().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPDNS=0\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_get_dns_servers_custom() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDNS:1,\"208.67.222.222\",\"2001:db8::1\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let servers = adapter.get_dns_servers().unwrap();
+
+    assert!(servers.custom);
+    assert_eq!(2, servers.servers.len());
+    assert_eq!(IpAddr::from_str("208.67.222.222").unwrap(), servers.servers[0]);
+    assert_eq!(IpAddr::from_str("2001:db8::1").unwrap(), servers.servers[1]);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!("AT+CIPDNS?\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_get_dns_servers_dhcp() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDNS:0,\"10.0.0.1\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let servers = adapter.get_dns_servers().unwrap();
+
+    assert!(!servers.custom);
+    assert_eq!(1, servers.servers.len());
+    assert_eq!(IpAddr::from_str("10.0.0.1").unwrap(), servers.servers[0]);
+}
+
+#[test]
+fn test_get_dns_servers_legacy_firmware() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDNS_CUR:208.67.222.222\r\n+CIPDNS_CUR:8.8.8.8\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.capabilities.temporary_dns_configuration = true;
+    let servers = adapter.get_dns_servers().unwrap();
+
+    assert!(!servers.custom);
+    assert_eq!(2, servers.servers.len());
+    assert_eq!(IpAddr::from_str("208.67.222.222").unwrap(), servers.servers[0]);
+    assert_eq!(IpAddr::from_str("8.8.8.8").unwrap(), servers.servers[1]);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPDNS_CUR?\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_get_dns_servers_invalid_response() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPDNS:1,\"invalid\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.get_dns_servers().unwrap_err();
+    assert_eq!(DnsError::ConfigurationError(AtError::Parse), error);
+}
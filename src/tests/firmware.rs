@@ -0,0 +1,195 @@
+use crate::firmware::{Capabilities, FirmwareError, Version};
+use crate::stack::Error;
+use crate::tests::mock::{connect_socket, MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+const ESP32_RESPONSE: &[u8] = b"AT version:3.2.0.0(s-ec2dec2 - ESP32 - Jul 28 2023 07:05:28)\r\n\
+SDK version:v5.0.2-376-g24b9d38a24-dirty\r\n\
+compile time(6118fc22):Jul 28 2023 07:14:30\r\n\
+Bin version:v3.2.0.0(ESP32-WROOM-32)\r\n";
+
+const ESP8266_RESPONSE: &[u8] = b"AT version:2.2.0.0(b097cdf - ESP8266 - Jun 17 2021 12:57:45)\r\n\
+SDK version:v3.4-22-g967752e2\r\n\
+compile time(6800286):Aug  4 2021 17:20:05\r\n\
+Bin version:2.2.0(Cytron_ESP-01S)\r\n";
+
+#[test]
+fn test_firmware_info_correct_command() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(ESP32_RESPONSE);
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.firmware_info().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+GMR\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_firmware_info_esp32() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(ESP32_RESPONSE);
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let info = adapter.firmware_info().unwrap();
+
+    assert_eq!(
+        "3.2.0.0(s-ec2dec2 - ESP32 - Jul 28 2023 07:05:28)",
+        info.at_version.as_str()
+    );
+    assert_eq!("v5.0.2-376-g24b9d38a24-dirty", info.sdk_version.as_str());
+    assert_eq!("Jul 28 2023 07:14:30", info.compile_time.as_str());
+    assert_eq!("v3.2.0.0(ESP32-WROOM-32)", info.bin_version.as_str());
+    assert_eq!(Some(Version::new(3, 2, 0)), info.version());
+
+    assert_eq!(Capabilities::default(), adapter.capabilities());
+}
+
+#[test]
+fn test_firmware_info_esp8266() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(ESP8266_RESPONSE);
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let info = adapter.firmware_info().unwrap();
+
+    assert_eq!("Aug  4 2021 17:20:05", info.compile_time.as_str());
+    assert_eq!(Some(Version::new(2, 2, 0)), info.version());
+
+    let capabilities = adapter.capabilities();
+    assert!(!capabilities.long_transmission);
+    assert!(capabilities.ipv6);
+    assert!(capabilities.receive_length_query);
+}
+
+#[test]
+fn test_firmware_info_legacy_format() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(
+        b"AT version:1.7.4.0(May 11 2020 19:13:04)\r\nSDK version:3.0.4(9532ceb)\r\n\
+compile time:May 27 2020 10:12:22\r\nBin version(Wroom 02):1.7.4\r\n",
+    );
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let info = adapter.firmware_info().unwrap();
+
+    assert_eq!("May 27 2020 10:12:22", info.compile_time.as_str());
+    assert_eq!("1.7.4", info.bin_version.as_str());
+    assert_eq!(Some(Version::new(1, 7, 4)), info.version());
+
+    let capabilities = adapter.capabilities();
+    assert!(!capabilities.long_transmission);
+    assert!(!capabilities.ipv6);
+    assert!(!capabilities.receive_length_query);
+    assert!(!capabilities.connection_state_query);
+    assert!(capabilities.temporary_dns_configuration);
+}
+
+#[test]
+fn test_firmware_info_unknown_version_format() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"AT version:custom build\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let info = adapter.firmware_info().unwrap();
+
+    assert_eq!(None, info.version());
+    assert_eq!(Capabilities::default(), adapter.capabilities());
+}
+
+#[test]
+fn test_firmware_info_missing_at_version() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"SDK version:v3.4-22-g967752e2\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.firmware_info().unwrap_err();
+    assert_eq!(FirmwareError::CommandError(AtError::Parse), error);
+}
+
+#[test]
+fn test_firmware_info_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.firmware_info().unwrap_err();
+    assert_eq!(FirmwareError::CommandError(AtError::Parse), error);
+}
+
+#[test]
+fn test_capabilities_from_version() {
+    let capabilities = Capabilities::from_version(Version::new(2, 4, 0));
+    assert!(capabilities.long_transmission);
+    assert!(capabilities.ipv6);
+    assert!(capabilities.receive_length_query);
+
+    let capabilities = Capabilities::from_version(Version::new(2, 0, 0));
+    assert!(!capabilities.long_transmission);
+    assert!(!capabilities.ipv6);
+    assert!(capabilities.receive_length_query);
+    assert!(capabilities.connection_state_query);
+    assert!(!capabilities.temporary_dns_configuration);
+}
+
+#[test]
+fn test_connect_ipv6_unsupported() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"AT version:2.0.0.0(ESP8266 - Jun 17 2020 12:57:45)\r\n");
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.firmware_info().unwrap();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter.client.reset_captured_commands();
+
+    let error = adapter
+        .connect(&mut socket, SocketAddr::from_str("[2001:db8::1]:8080").unwrap())
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::Ipv6Unsupported), error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_send_long_transmission_unsupported() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let mut client = MockAtatClient::new();
+    client.add_response(ESP8266_RESPONSE);
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.firmware_info().unwrap();
+    adapter.set_long_transmission(true);
+
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+
+    assert_eq!(4, adapter.send(&mut socket, b"test").unwrap());
+
+    // Falls back to CIPSEND
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPSEND=0,4\r\n".to_string(), commands[0]);
+}
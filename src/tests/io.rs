@@ -0,0 +1,167 @@
+use crate::io::TcpConnection;
+use crate::stack::Error;
+use crate::tests::mock::{connect_socket, MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use alloc::vec;
+use embedded_io::{Error as IoError, ErrorKind, Read, Write};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_write() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    // TX prepare command + actual TX command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+    adapter.client.add_urc_recv_bytes();
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    assert_eq!(4, connection.write(b"test").unwrap());
+    connection.flush().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(5, commands.len());
+    assert_eq!("AT+CIPSEND=0,4\r\n".to_string(), commands[3]);
+    assert_eq!("test".to_string(), commands[4]);
+}
+
+#[test]
+fn test_write_flush_read_pending_block() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(3).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+
+    let mut buffer = vec![b'A'; 256];
+    buffer.extend_from_slice(b"test");
+
+    // Second block is still unconfirmed, but included
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    assert_eq!(260, connection.write(buffer.as_slice()).unwrap());
+
+    adapter.client.add_urc_send_ok();
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    connection.flush().unwrap();
+    assert!(adapter.pending_send.is_none());
+
+    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,4:aaaa");
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    let mut read_buffer = [b' '; 16];
+    assert_eq!(4, connection.read(&mut read_buffer).unwrap());
+    assert_eq!(b"aaaa", &read_buffer[..4]);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(8, commands.len());
+    assert_eq!("AT+CIPSEND=0,4\r\n".to_string(), commands[5]);
+    assert_eq!("test".to_string(), commands[6]);
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[7]);
+}
+
+#[test]
+fn test_write_empty_buffer() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    assert_eq!(0, connection.write(b"").unwrap());
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_write_unconnected_socket() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    let error = connection.write(b"test").unwrap_err();
+    assert_eq!(Error::SocketUnconnected, error);
+    assert_eq!(ErrorKind::NotConnected, error.kind());
+}
+
+#[test]
+fn test_read() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,4\r\n");
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,4:aaaa");
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    let mut buffer = [b' '; 16];
+    assert_eq!(4, connection.read(&mut buffer).unwrap());
+    assert_eq!(b"aaaa", &buffer[..4]);
+}
+
+#[test]
+fn test_read_remotely_closed() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_first_socket_closed();
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    let mut buffer = [b' '; 16];
+    assert_eq!(0, connection.read(&mut buffer).unwrap());
+}
+
+#[test]
+fn test_read_empty_buffer() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    let mut connection = TcpConnection::new(&mut adapter, &mut socket);
+    assert_eq!(0, connection.read(&mut []).unwrap());
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_error_kind() {
+    assert_eq!(ErrorKind::ConnectionReset, Error::ClosingSocket.kind());
+    assert_eq!(
+        ErrorKind::ConnectionRefused,
+        Error::ConnectError(atat::Error::Timeout).kind()
+    );
+    assert_eq!(ErrorKind::InvalidInput, Error::BoundSocket.kind());
+    assert_eq!(ErrorKind::Other, Error::TimerError.kind());
+}
@@ -1,7 +1,11 @@
+use crate::stack::Socket;
+use crate::wifi::Adapter;
 use alloc::collections::VecDeque;
 use alloc::vec;
 use alloc::vec::Vec;
 use atat::{AtatClient, AtatCmd, AtatUrc, Error, Mode};
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack};
 use fugit::{TimerDurationU32, TimerInstantU32};
 use fugit_timer::Timer as FugitTimer;
 use mockall::mock;
@@ -38,6 +42,9 @@ pub struct MockAtatClient {
 
     /// Simulates a 'WouldBlock' response at given call index
     send_would_block: Option<usize>,
+
+    /// Errors returned by check_response(), simulating responses received while no command is pending
+    late_responses: VecDeque<Error>,
 }
 
 impl AtatClient for MockAtatClient {
@@ -90,7 +97,10 @@ impl AtatClient for MockAtatClient {
     }
 
     fn check_response<A: AtatCmd<LEN>, const LEN: usize>(&mut self, _cmd: &A) -> nb::Result<A::Response, Error> {
-        nb::Result::Err(nb::Error::WouldBlock)
+        match self.late_responses.pop_front() {
+            None => nb::Result::Err(nb::Error::WouldBlock),
+            Some(error) => nb::Result::Err(nb::Error::Other(error)),
+        }
     }
 
     fn get_mode(&self) -> Mode {
@@ -119,6 +129,7 @@ impl MockAtatClient {
             reset_call_count: 0,
             expect_reset_call: false,
             send_would_block: None,
+            late_responses: VecDeque::new(),
         }
     }
 
@@ -143,6 +154,16 @@ impl MockAtatClient {
         self.add_response(b"");
     }
 
+    /// Simulates an OK response received after the command has been sent without waiting (unconfirmed command)
+    pub fn add_late_ok_response(&mut self) {
+        self.late_responses.push_back(Error::InvalidResponse);
+    }
+
+    /// Simulates an error response received after the command has been sent without waiting (unconfirmed command)
+    pub fn add_late_error_response(&mut self) {
+        self.late_responses.push_back(Error::Error);
+    }
+
     /// Adds a  mock URC message
     pub fn add_urc_message(&mut self, message: &'static [u8]) {
         self.urc_messages.push_back(message);
@@ -258,4 +279,30 @@ impl MockTimer {
     pub fn duration_ms(duration: u32) -> TimerDurationU32<1_000_000> {
         TimerDurationU32::millis(duration)
     }
+
+    /// Short hand helper for returning the instant after the given milliseconds
+    pub fn instant_ms(ms: u32) -> TimerInstantU32<1_000_000> {
+        TimerInstantU32::from_ticks(0) + TimerDurationU32::millis(ms)
+    }
+}
+
+/// Connects the first socket of a fresh adapter to 127.0.0.1:5000
+pub fn connect_socket<const TX_SIZE: usize, const RX_SIZE: usize, const SOCKETS: usize>(
+    adapter: &mut Adapter<MockAtatClient, MockTimer, 1_000_000, TX_SIZE, RX_SIZE, SOCKETS>,
+) -> Socket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+
+    // Receiving mode + connect command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+
+    socket
 }
@@ -1,6 +1,21 @@
+#[cfg(feature = "active-mode")]
+mod active;
 mod address;
 mod buffer;
+mod dns;
+mod firmware;
+#[cfg(feature = "embedded-io")]
+mod io;
 mod mock;
+mod passthrough;
+mod receive_length;
+mod reset;
+mod resync;
+mod server;
+mod socket_options;
+mod soft_ap;
 mod stack;
+mod tls;
+mod udp;
 mod urc;
 mod wifi;
@@ -0,0 +1,375 @@
+use crate::dns::DnsError;
+use crate::firmware::FirmwareError;
+use crate::passthrough::PassthroughSocket;
+use crate::stack::Error;
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::wifi::{Adapter, JoinError, WifiAdapter};
+use alloc::collections::VecDeque;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_hal::serial;
+use embedded_nal::{AddrType, Dns, IpAddr, SocketAddr, TcpClientStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 8, 4>;
+
+#[test]
+fn test_connect_passthrough_correct_commands() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let socket = connect_passthrough(&mut adapter);
+    assert_eq!(SocketAddr::from_str("10.0.0.1:21").unwrap(), socket.remote());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CIPMUX=0\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPMODE=1\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPSTART=\"TCP\",\"10.0.0.1\",21\r\n".to_string(), commands[2]);
+    assert_eq!("AT+CIPSEND\r\n".to_string(), commands[3]);
+}
+
+#[test]
+fn test_connect_passthrough_ipv6() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.skip_urc(1);
+    client.add_urc_message(b"CONNECT\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter
+        .connect_passthrough(SocketAddr::from_str("[2001:db8::1]:8080").unwrap())
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(
+        "AT+CIPSTART=\"TCPv6\",\"2001:0db8:0:0:0:0:0:0001\",8080\r\n".to_string(),
+        commands[2]
+    );
+}
+
+#[test]
+fn test_connect_passthrough_sockets_in_use() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.socket().unwrap();
+    adapter.client.reset_captured_commands();
+
+    let error = adapter.connect_passthrough(remote()).unwrap_err();
+    assert_eq!(Error::SocketsInUse, error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_connect_passthrough_already_active() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    connect_passthrough(&mut adapter);
+
+    let error = adapter.connect_passthrough(remote()).unwrap_err();
+    assert_eq!(Error::PassthroughActive, error);
+}
+
+#[test]
+fn test_connect_passthrough_single_connection_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.connect_passthrough(remote()).unwrap_err();
+    assert_eq!(Error::EnablingSingleConnectionFailed(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_passthrough_transmission_mode_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.connect_passthrough(remote()).unwrap_err();
+    assert_eq!(Error::TransmissionModeError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_passthrough_unconfirmed() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    // Reset of transmission mode
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.connect_passthrough(remote()).unwrap_err();
+    assert_eq!(Error::UnconfirmedSocketState, error);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CIPMODE=0\r\n".to_string(), commands[3]);
+}
+
+#[test]
+fn test_connect_passthrough_send_error_closes_connection() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_error_response();
+    // Closing connection and reset of transmission mode
+    client.add_ok_response();
+    client.add_ok_response();
+    client.skip_urc(1);
+    client.add_urc_message(b"CONNECT\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.connect_passthrough(remote()).unwrap_err();
+    assert_eq!(Error::TransmissionStartFailed(AtError::Parse), error);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(6, commands.len());
+    assert_eq!("AT+CIPCLOSE\r\n".to_string(), commands[4]);
+    assert_eq!("AT+CIPMODE=0\r\n".to_string(), commands[5]);
+}
+
+#[test]
+fn test_send_passthrough_chunks() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_passthrough(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    assert_eq!(12, adapter.send_passthrough(&mut socket, b"0123456789ab").unwrap());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("01234567".to_string(), commands[0]);
+    assert_eq!("89ab".to_string(), commands[1]);
+}
+
+#[test]
+fn test_send_passthrough_error() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_passthrough(&mut adapter);
+
+    adapter.client.add_error_response();
+    let error = adapter.send_passthrough(&mut socket, b"data").unwrap_err();
+    assert_eq!(Error::SendFailed(AtError::Parse), error);
+}
+
+#[test]
+fn test_socket_while_passthrough_active() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    connect_passthrough(&mut adapter);
+
+    assert_eq!(Error::PassthroughActive, adapter.socket().unwrap_err());
+}
+
+#[test]
+fn test_commands_while_passthrough_active() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    connect_passthrough(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    assert_eq!(
+        JoinError::PassthroughActive,
+        adapter.join("test_wifi", "secret").unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::Other(DnsError::PassthroughActive),
+        adapter.get_host_by_name("example.org", AddrType::Either).unwrap_err()
+    );
+    assert_eq!(
+        DnsError::PassthroughActive,
+        adapter.set_dns_servers(&[IpAddr::from_str("1.1.1.1").unwrap()]).unwrap_err()
+    );
+    assert_eq!(FirmwareError::PassthroughActive, adapter.firmware_info().unwrap_err());
+    assert_eq!(Error::PassthroughActive, adapter.resync_connections().unwrap_err());
+
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_passthrough() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_passthrough(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    let mut rx = MockSerial::new(b"hallo!");
+    let mut buffer = [0x0; 4];
+    assert_eq!(
+        4,
+        adapter.receive_passthrough(&mut socket, &mut rx, &mut buffer).unwrap()
+    );
+    assert_eq!(b"hall", &buffer);
+    assert_eq!(
+        2,
+        adapter.receive_passthrough(&mut socket, &mut rx, &mut buffer).unwrap()
+    );
+    assert_eq!(b"o!", &buffer[..2]);
+
+    let error = adapter.receive_passthrough(&mut socket, &mut rx, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+
+    // Data is not processed by ATAT
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_passthrough_not_active() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let mut socket = PassthroughSocket::new(remote());
+    let mut rx = MockSerial::new(b"data");
+    let error = adapter.receive_passthrough(&mut socket, &mut rx, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SocketUnconnected), error);
+}
+
+#[test]
+fn test_close_passthrough_correct_commands() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(2).returning(|duration| {
+        assert!(duration == MockTimer::duration_ms(20) || duration == MockTimer::duration_ms(1_000));
+        Ok(())
+    });
+    timer.expect_wait().times(2).returning(|| nb::Result::Ok(()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_passthrough(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.close_passthrough(socket).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("+++".to_string(), commands[0]);
+    assert_eq!("AT+CIPMODE=0\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPCLOSE\r\n".to_string(), commands[2]);
+
+    // Multiple connections are enabled again
+    adapter.client.add_ok_response();
+    adapter.socket().unwrap();
+    assert_eq!(
+        "AT+CIPMUX=1\r\n".to_string(),
+        adapter.client.get_commands_as_strings()[3]
+    );
+}
+
+#[test]
+fn test_close_passthrough_remotely_closed() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(2).returning(|_| Ok(()));
+    timer.expect_wait().times(2).returning(|| nb::Result::Ok(()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_passthrough(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_urc_message(b"CLOSED\r\n");
+    adapter.close_passthrough(socket).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPMODE=0\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_close_passthrough_timer_error() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Err(1));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_passthrough(&mut adapter);
+
+    let error = adapter.close_passthrough(socket).unwrap_err();
+    assert_eq!(Error::TimerError, error);
+}
+
+#[test]
+fn test_close_passthrough_mode_error() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(2).returning(|_| Ok(()));
+    timer.expect_wait().times(2).returning(|| nb::Result::Ok(()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_passthrough(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_error_response();
+    let error = adapter.close_passthrough(socket).unwrap_err();
+    assert_eq!(Error::TransmissionModeError(AtError::Parse), error);
+
+    // Passthrough mode was left by escape sequence
+    assert!(!adapter.passthrough_active);
+}
+
+fn remote() -> SocketAddr {
+    SocketAddr::from_str("10.0.0.1:21").unwrap()
+}
+
+fn connect_passthrough(adapter: &mut AdapterType) -> PassthroughSocket {
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_message(b"CONNECT\r\n");
+
+    adapter.connect_passthrough(remote()).unwrap()
+}
+
+/// Serial RX returning the given bytes
+struct MockSerial {
+    data: VecDeque<u8>,
+}
+
+impl MockSerial {
+    fn new(data: &[u8]) -> Self {
+        Self {
+            data: data.iter().copied().collect(),
+        }
+    }
+}
+
+impl serial::Read<u8> for MockSerial {
+    type Error = ();
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.data.pop_front().ok_or(nb::Error::WouldBlock)
+    }
+}
@@ -0,0 +1,258 @@
+use crate::stack::Error;
+use crate::tests::mock::{connect_socket, MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_query_receive_lengths_correct_command() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPRECVLEN:0,0,0,0,0\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!([0; 5], adapter.query_receive_lengths().unwrap());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPRECVLEN?\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_query_receive_lengths_updates_data_available() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPRECVLEN:12,,-1,1024,0\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.data_available[2] = 8;
+
+    assert_eq!([12, 0, 0, 1024, 0], adapter.query_receive_lengths().unwrap());
+    assert_eq!([12, 0, 0, 1024, 0], adapter.data_available);
+}
+
+#[test]
+fn test_query_receive_lengths_fewer_links() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPRECVLEN:5,7\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!([5, 7, 0, 0, 0], adapter.query_receive_lengths().unwrap());
+}
+
+#[test]
+fn test_query_receive_lengths_more_than_five_links() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPRECVLEN:0,1,2,3,4,5,6,7\r\n");
+
+    let mut adapter: Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4, 8> = Adapter::new(client, timer);
+    assert_eq!([0, 1, 2, 3, 4, 5, 6, 7], adapter.query_receive_lengths().unwrap());
+    assert_eq!([0, 1, 2, 3, 4, 5, 6, 7], adapter.data_available);
+}
+
+#[test]
+fn test_query_receive_lengths_invalid_response() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPRECVLEN:5,abc\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.query_receive_lengths().unwrap_err();
+    assert_eq!(Error::ReceiveLengthQueryFailed(AtError::Parse), error);
+}
+
+#[test]
+fn test_query_receive_lengths_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.query_receive_lengths().unwrap_err();
+    assert_eq!(Error::ReceiveLengthQueryFailed(AtError::Parse), error);
+}
+
+#[test]
+fn test_query_receive_lengths_unsupported() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.capabilities.receive_length_query = false;
+
+    let error = adapter.query_receive_lengths().unwrap_err();
+    assert_eq!(Error::ReceiveLengthQueryUnsupported, error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_polling_disabled_by_default() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    for _ in 0..10 {
+        let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+        assert_eq!(nb::Error::WouldBlock, error);
+    }
+
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_polling_missed_data_message() {
+    let mut timer = MockTimer::new();
+    let mut instants = [0, 1_000, 3_000].into_iter();
+    timer
+        .expect_now()
+        .times(3)
+        .returning(move || MockTimer::instant_ms(instants.next().unwrap()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_receive_length_polling(Some(3_000));
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    for _ in 0..2 {
+        let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+        assert_eq!(nb::Error::WouldBlock, error);
+    }
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+
+    adapter.client.add_response(b"+CIPRECVLEN:3,0,0,0,0\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(2);
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+
+    let mut buffer = [b' '; 4];
+    assert_eq!(3, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"abc ", &buffer);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPRECVLEN?\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_receive_polling_no_data() {
+    let mut timer = MockTimer::new();
+    let mut instants = [0, 2_000, 2_500].into_iter();
+    timer
+        .expect_now()
+        .times(3)
+        .returning(move || MockTimer::instant_ms(instants.next().unwrap()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_receive_length_polling(Some(2_000));
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_response(b"+CIPRECVLEN:0,0,0,0,0\r\n");
+    for _ in 0..3 {
+        let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+        assert_eq!(nb::Error::WouldBlock, error);
+    }
+
+    // Interval is restarted after polling
+    assert_eq!(1, adapter.client.get_commands_as_strings().len());
+}
+
+#[test]
+fn test_receive_polling_reset_by_data_message() {
+    let mut timer = MockTimer::new();
+    let mut instants = [0, 2_500].into_iter();
+    timer
+        .expect_now()
+        .times(2)
+        .returning(move || MockTimer::instant_ms(instants.next().unwrap()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_receive_length_polling(Some(2_000));
+    let mut socket = connect_socket(&mut adapter);
+
+    let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+
+    adapter.client.add_urc_message(b"+IPD,0,2\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,2:ab");
+    assert_eq!(2, adapter.receive(&mut socket, &mut [0x0; 4]).unwrap());
+    adapter.client.reset_captured_commands();
+
+    let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_polling_unsupported_by_firmware() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_receive_length_polling(Some(0));
+    adapter.capabilities.receive_length_query = false;
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_receive_polling_command_error() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_receive_length_polling(Some(0));
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_error_response();
+    let error = adapter.receive(&mut socket, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::ReceiveLengthQueryFailed(AtError::Parse)), error);
+}
+
+#[test]
+fn test_udp_receive_polling_missed_data_message() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let mut client = MockAtatClient::new();
+    // Multiple connections, receiving mode + connect command
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.skip_urc(1);
+    client.add_urc_first_socket_connected();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_receive_length_polling(Some(0));
+
+    let mut socket = embedded_nal::UdpClientStack::socket(&mut adapter).unwrap();
+    embedded_nal::UdpClientStack::connect(&mut adapter, &mut socket, SocketAddr::from_str("10.0.0.1:53").unwrap())
+        .unwrap();
+
+    adapter.client.add_response(b"+CIPRECVLEN:3,0,0,0,0\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(2);
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+
+    let mut buffer = [b' '; 4];
+    let (length, _) = embedded_nal::UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(3, length);
+    assert_eq!(b"abc ", &buffer);
+}
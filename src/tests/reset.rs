@@ -0,0 +1,236 @@
+use crate::reset::ResetError;
+use crate::stack::{PendingSend, SocketState};
+use crate::tests::mock::{connect_socket, MockAtatClient, MockTimer};
+use crate::wifi::{Adapter, WifiAdapter};
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::cell::Cell;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack, TcpFullStack};
+use heapless::Vec;
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_reset_correct_commands() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|duration| {
+        assert_eq!(duration, MockTimer::duration_ms(5_000));
+        Ok(())
+    });
+
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.skip_urc(1);
+    client.add_urc_message(b"ready\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.reset().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+RST\r\n".to_string(), commands[0]);
+
+    // Restart on purpose is not signaled
+    assert!(!adapter.modem_restarted());
+}
+
+#[test]
+fn test_reset_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(ResetError::CommandError(AtError::Parse), adapter.reset().unwrap_err());
+}
+
+#[test]
+fn test_reset_timeout() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_wait().times(2).returning({
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            match calls {
+                1 => nb::Result::Err(nb::Error::WouldBlock),
+                _ => nb::Result::Ok(()),
+            }
+        }
+    });
+
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(ResetError::Timeout, adapter.reset().unwrap_err());
+}
+
+#[test]
+fn test_reset_timer_error() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Err(1));
+
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(ResetError::TimerError, adapter.reset().unwrap_err());
+}
+
+#[test]
+fn test_hardware_reset() {
+    let mut timer = MockTimer::new();
+    timer.expect_start().times(1).returning(|_| Ok(()));
+
+    let mut client = MockAtatClient::new();
+    client.skip_urc(1);
+    client.add_urc_message(b"ready\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let toggled = Cell::new(false);
+    adapter.hardware_reset(|| toggled.set(true)).unwrap();
+
+    assert!(toggled.get());
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+    assert!(!adapter.modem_restarted());
+}
+
+#[test]
+fn test_unexpected_restart_signaled_once() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    assert!(!adapter.modem_restarted());
+
+    adapter.client.add_urc_message(b"ready\r\n");
+    assert!(adapter.modem_restarted());
+    assert!(!adapter.modem_restarted());
+}
+
+#[test]
+fn test_unexpected_restart_resets_wifi_state() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    adapter.client.add_urc_message(b"WIFI CONNECTED\r\n");
+    adapter.client.add_urc_message(b"WIFI GOT IP\r\n");
+    assert!(adapter.get_join_status().ip_assigned);
+
+    adapter.client.add_urc_message(b"ready\r\n");
+    let state = adapter.get_join_status();
+    assert!(!state.connected);
+    assert!(!state.ip_assigned);
+}
+
+#[test]
+fn test_unexpected_restart_closes_sockets() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,0,16\r\n");
+    adapter.client.add_urc_message(b"ready\r\n");
+
+    assert!(!adapter.is_connected(&socket).unwrap());
+    assert_eq!(0, adapter.data_available[0]);
+
+    // No close command is sent, as connection is already lost
+    adapter.client.reset_captured_commands();
+    adapter.close(socket).unwrap();
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_unexpected_restart_stops_server() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // Multiple connections, receiving mode, server max connections and server command
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = adapter.socket().unwrap();
+    adapter.bind(&mut server, 80).unwrap();
+    adapter.listen(&mut server).unwrap();
+
+    adapter.client.add_urc_message(b"1,CONNECT\r\n");
+    adapter.client.add_urc_message(b"ready\r\n");
+    assert!(adapter.modem_restarted());
+
+    assert_eq!(None, adapter.server_port);
+    assert_eq!(SocketState::Closed, adapter.sockets[1]);
+}
+
+#[test]
+fn test_unexpected_restart_resends_configuration() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"ready\r\n");
+    assert!(adapter.modem_restarted());
+    adapter.close(socket).unwrap();
+    adapter.client.reset_captured_commands();
+
+    connect_socket(&mut adapter);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("AT+CIPMUX=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPRECVMODE=1\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_unexpected_restart_resets_session_state() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\n");
+    adapter.pending_send = Some(PendingSend {
+        link_id: 0,
+        length: 4,
+        deadline: MockTimer::instant_ms(5_000),
+    });
+    adapter.send_progress = Some(2);
+    adapter.send_confirmed = Some(true);
+    adapter.recv_byte_count = Some(4);
+    adapter.already_connected = true;
+    adapter.connecting = [true; 5];
+    adapter.station_enabled = true;
+    adapter.soft_ap_enabled = true;
+    adapter.unsupported_link_id = Some(7);
+    adapter.receive_poll_deadlines = [Some(MockTimer::instant_ms(1_000)); 5];
+    adapter.data = Some(Vec::from_slice(b"data").unwrap());
+    adapter.data_remote = Some(SocketAddr::from_str("10.0.0.1:53").unwrap());
+
+    adapter.client.add_urc_message(b"ready\r\n");
+    assert!(adapter.modem_restarted());
+
+    assert_eq!(None, adapter.pending_send);
+    assert_eq!(None, adapter.send_progress);
+    assert_eq!(None, adapter.send_confirmed);
+    assert_eq!(None, adapter.recv_byte_count);
+    assert!(!adapter.already_connected);
+    assert_eq!([false; 5], adapter.connecting);
+    assert!(!adapter.station_enabled);
+    assert!(!adapter.soft_ap_enabled);
+    assert_eq!(None, adapter.unsupported_link_id);
+    assert_eq!([None; 5], adapter.receive_poll_deadlines);
+    assert_eq!(None, adapter.data);
+    assert_eq!(None, adapter.data_remote);
+    assert_eq!(None, adapter.poll_station_event());
+    assert!(!adapter.multi_connections_enabled);
+    assert!(!adapter.receiving_mode_configured);
+    assert!(!adapter.data_info_enabled);
+}
@@ -0,0 +1,329 @@
+use crate::stack::{ConnectionInfo, Error, Socket, SocketState};
+use crate::tests::mock::{connect_socket, MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack, TcpFullStack};
+use heapless::String;
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_resync_correct_command() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.resync_connections().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPSTATE?\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_resync_connection_details() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n+CIPSTATE:2,\"UDP\",\"2001:db8::1\",53,4000,0\r\n",
+    );
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let connections = adapter.resync_connections().unwrap();
+
+    assert_eq!(2, connections.len());
+    assert_eq!(
+        ConnectionInfo {
+            link_id: 0,
+            protocol: String::from("TCP"),
+            remote: SocketAddr::from_str("10.0.0.1:5000").unwrap(),
+            local_port: 12000,
+            server: false,
+        },
+        connections[0]
+    );
+    assert_eq!(2, connections[1].link_id);
+    assert_eq!("UDP", connections[1].protocol.as_str());
+    assert_eq!(SocketAddr::from_str("[2001:db8::1]:53").unwrap(), connections[1].remote);
+}
+
+#[test]
+fn test_resync_legacy_firmware() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"STATUS:3\r\n+CIPSTATUS:1,\"TCP\",\"10.0.0.5\",51234,80,1\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.capabilities.connection_state_query = false;
+    let connections = adapter.resync_connections().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPSTATUS\r\n".to_string(), commands[0]);
+
+    assert_eq!(1, connections.len());
+    assert_eq!(1, connections[0].link_id);
+    assert_eq!(SocketAddr::from_str("10.0.0.5:51234").unwrap(), connections[0].remote);
+    assert_eq!(80, connections[0].local_port);
+    assert!(connections[0].server);
+}
+
+#[test]
+fn test_resync_legacy_firmware_invalid_line() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"STATUS:3\r\n+CIPSTATUS:1,\"TCP\",\"10.0.0.5\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.capabilities.connection_state_query = false;
+
+    let error = adapter.resync_connections().unwrap_err();
+    assert_eq!(Error::ConnectionStateError(AtError::Parse), error);
+}
+
+#[test]
+fn test_resync_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.resync_connections().unwrap_err();
+    assert_eq!(Error::ConnectionStateError(AtError::Parse), error);
+}
+
+#[test]
+fn test_resync_invalid_remote_ip() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPSTATE:0,\"TCP\",\"invalid\",5000,12000,0\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.resync_connections().unwrap_err();
+    assert_eq!(Error::ConnectionStateError(AtError::InvalidResponse), error);
+}
+
+#[test]
+fn test_resync_connected_socket_kept() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter
+        .client
+        .add_response(b"+CIPSTATE:0,\"TCP\",\"127.0.0.1\",5000,12000,0\r\n");
+    adapter.resync_connections().unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+}
+
+#[test]
+fn test_resync_missed_close() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_response(b"");
+    assert!(adapter.resync_connections().unwrap().is_empty());
+    assert!(!adapter.is_connected(&socket).unwrap());
+
+    // No close command is sent, as connection is already closed
+    adapter.client.reset_captured_commands();
+    adapter.close(socket).unwrap();
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_resync_missed_connect() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // Multiple connections, receiving mode and connect command
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let error = adapter
+        .connect(&mut socket, SocketAddr::from_str("10.0.0.1:5000").unwrap())
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::UnconfirmedSocketState), error);
+
+    adapter
+        .client
+        .add_response(b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n");
+    adapter.resync_connections().unwrap();
+
+    assert!(adapter.is_connected(&socket).unwrap());
+    assert_eq!(Some(SocketAddr::from_str("10.0.0.1:5000").unwrap()), adapter.remotes[0]);
+    assert!(!adapter.connecting[0]);
+}
+
+#[test]
+fn test_resync_unconnected_socket_not_promoted() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // Multiple connections command
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = adapter.socket().unwrap();
+
+    adapter
+        .client
+        .add_response(b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n");
+    adapter.resync_connections().unwrap();
+
+    assert!(!adapter.is_connected(&socket).unwrap());
+    assert_eq!(SocketState::Open, adapter.sockets[0]);
+}
+
+#[test]
+fn test_resync_reserved_socket_shares_link_with_incoming_connection() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    let socket = adapter.socket().unwrap();
+    assert_eq!(0, socket.link_id);
+
+    adapter.client.add_response(b"+CIPSTATE:0,\"TCP\",\"10.0.0.5\",51234,80,1\r\n");
+    adapter.resync_connections().unwrap();
+
+    // Neither connected nor handed out again by accept()
+    assert!(!adapter.is_connected(&socket).unwrap());
+    assert_eq!(SocketState::Open, adapter.sockets[0]);
+    assert_eq!(nb::Error::WouldBlock, adapter.accept(&mut server).unwrap_err());
+}
+
+#[test]
+fn test_resync_closing_socket_not_reconnected() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"0,CLOSED\r\n");
+    assert!(!adapter.is_connected(&socket).unwrap());
+
+    // Link id got reused by ESP-AT for another connection
+    adapter
+        .client
+        .add_response(b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n");
+    adapter.resync_connections().unwrap();
+
+    assert!(!adapter.is_connected(&socket).unwrap());
+    assert_eq!(SocketState::Closing, adapter.sockets[0]);
+}
+
+#[test]
+fn test_resync_invalid_entry_keeps_socket_states() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    listen(&mut adapter, 80);
+
+    adapter
+        .client
+        .add_response(b"+CIPSTATE:2,\"TCP\",\"10.0.0.5\",51234,80,1\r\n+CIPSTATE:3,\"TCP\",\"invalid\",51235,80,1\r\n");
+    let error = adapter.resync_connections().unwrap_err();
+
+    assert_eq!(Error::ConnectionStateError(AtError::InvalidResponse), error);
+    assert_eq!(SocketState::Closed, adapter.sockets[2]);
+}
+
+#[test]
+fn test_resync_missed_incoming_connection() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    adapter.client.add_response(b"+CIPSTATE:2,\"TCP\",\"10.0.0.5\",51234,80,1\r\n");
+    adapter.resync_connections().unwrap();
+    assert_eq!(SocketState::Incoming, adapter.sockets[2]);
+
+    adapter.client.add_response(b"+CIPSTATE:2,\"TCP\",\"10.0.0.5\",51234,80,1\r\n");
+    let (socket, remote) = adapter.accept(&mut server).unwrap();
+    assert_eq!(2, socket.link_id);
+    assert_eq!(SocketAddr::from_str("10.0.0.5:51234").unwrap(), remote);
+}
+
+#[test]
+fn test_resync_incoming_connection_without_server() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPSTATE:2,\"TCP\",\"10.0.0.5\",51234,80,1\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.resync_connections().unwrap();
+    assert_eq!(SocketState::Closed, adapter.sockets[2]);
+}
+
+#[test]
+fn test_resync_missed_close_of_incoming_connection() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    adapter.client.add_urc_message(b"2,CONNECT\r\n");
+    adapter.client.add_response(b"");
+    adapter.resync_connections().unwrap();
+    assert_eq!(SocketState::Closed, adapter.sockets[2]);
+
+    let error = adapter.accept(&mut server).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
+#[test]
+fn test_resync_ignores_links_exceeding_sockets() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CIPSTATE:5,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert!(adapter.resync_connections().unwrap().is_empty());
+}
+
+#[test]
+fn test_resync_more_than_five_links() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n+CIPSTATE:1,\"TCP\",\"10.0.0.1\",5001,12001,0\r\n\
++CIPSTATE:2,\"TCP\",\"10.0.0.1\",5002,12002,0\r\n+CIPSTATE:3,\"TCP\",\"10.0.0.1\",5003,12003,0\r\n\
++CIPSTATE:5,\"UDP\",\"10.0.0.1\",5005,12005,0\r\n+CIPSTATE:7,\"TCP\",\"10.0.0.1\",5007,12007,0\r\n",
+    );
+
+    let mut adapter: Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4, 8> = Adapter::new(client, timer);
+    let connections = adapter.resync_connections().unwrap();
+
+    assert_eq!(6, connections.len());
+    assert_eq!(5, connections[4].link_id);
+    assert_eq!(7, connections[5].link_id);
+    assert_eq!(SocketAddr::from_str("10.0.0.1:5007").unwrap(), connections[5].remote);
+}
+
+/// Helper for starting a server on the given port
+fn listen(adapter: &mut AdapterType, port: u16) -> Socket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+    // Receiving mode, max. connections and server command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter.bind(&mut socket, port).unwrap();
+    adapter.listen(&mut socket).unwrap();
+
+    socket
+}
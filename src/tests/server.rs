@@ -0,0 +1,270 @@
+use crate::stack::{Error, Socket};
+use crate::tests::mock::{connect_socket, MockAtatClient, MockTimer};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack, TcpFullStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_bind_releases_link_id() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+    assert_eq!(0, socket.link_id);
+
+    adapter.bind(&mut socket, 80).unwrap();
+    assert_eq!(0, adapter.socket().unwrap().link_id);
+}
+
+#[test]
+fn test_bind_already_connected() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    let error = adapter.bind(&mut socket, 80).unwrap_err();
+    assert_eq!(Error::AlreadyConnected, error);
+}
+
+#[test]
+fn test_listen_unbound_socket() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let error = adapter.listen(&mut socket).unwrap_err();
+    assert_eq!(Error::UnboundSocket, error);
+}
+
+#[test]
+fn test_listen_correct_commands() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_server_max_connections(3);
+
+    listen(&mut adapter, 8080);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CIPMUX=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPRECVMODE=1\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPSERVERMAXCONN=3\r\n".to_string(), commands[2]);
+    assert_eq!("AT+CIPSERVER=1,8080\r\n".to_string(), commands[3]);
+}
+
+#[test]
+fn test_listen_server_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Multiple connections command
+    client.add_ok_response();
+    // Receiving mode command
+    client.add_ok_response();
+    // Max. connections command
+    client.add_ok_response();
+    // Server command
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+    adapter.bind(&mut socket, 80).unwrap();
+
+    let error = adapter.listen(&mut socket).unwrap_err();
+    assert_eq!(Error::ServerError(AtError::Parse), error);
+}
+
+#[test]
+fn test_listen_server_already_running() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    let mut socket = adapter.socket().unwrap();
+    adapter.bind(&mut socket, 81).unwrap();
+
+    assert_eq!(Error::ServerAlreadyRunning, adapter.listen(&mut socket).unwrap_err());
+    assert_eq!(Error::ServerAlreadyRunning, adapter.bind(&mut server, 82).unwrap_err());
+}
+
+#[test]
+fn test_accept_not_listening() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let error = adapter.accept(&mut socket).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::UnboundSocket), error);
+
+    adapter.bind(&mut socket, 80).unwrap();
+    let error = adapter.accept(&mut socket).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::NotListening), error);
+}
+
+#[test]
+fn test_accept_no_incoming_connection() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    let error = adapter.accept(&mut server).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
+#[test]
+fn test_accept_incoming_connection() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    adapter.client.reset_captured_commands();
+    adapter.client.add_urc_message(b"2,CONNECT\r\n");
+    adapter.client.add_response(
+        b"+CIPSTATE:0,\"TCP\",\"10.0.0.1\",5000,12000,0\r\n+CIPSTATE:2,\"TCP\",\"10.0.0.5\",51234,80,1\r\n",
+    );
+
+    let (socket, remote) = adapter.accept(&mut server).unwrap();
+    assert_eq!(2, socket.link_id);
+    assert_eq!(SocketAddr::from_str("10.0.0.5:51234").unwrap(), remote);
+    assert!(adapter.is_connected(&socket).unwrap());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPSTATE?\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_accept_connection_closed_before_accepted() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    adapter.client.add_urc_message(b"2,CONNECT\r\n");
+    adapter.client.add_urc_message(b"2,CLOSED\r\n");
+
+    let error = adapter.accept(&mut server).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
+#[test]
+fn test_accept_connection_missing_in_state() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    adapter.client.add_urc_message(b"0,CONNECT\r\n");
+    adapter.client.add_response(b"");
+
+    let error = adapter.accept(&mut server).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+
+    // Link is available for reuse
+    assert_eq!(0, adapter.socket().unwrap().link_id);
+}
+
+#[test]
+fn test_accept_state_command_error() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    adapter.client.add_urc_message(b"0,CONNECT\r\n");
+    adapter.client.add_error_response();
+
+    let error = adapter.accept(&mut server).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::ConnectionStateError(AtError::Parse)), error);
+}
+
+#[test]
+fn test_bound_socket_not_usable_as_client() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut server = listen(&mut adapter, 80);
+
+    let error = adapter
+        .connect(&mut server, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::BoundSocket), error);
+
+    let error = adapter.send(&mut server, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::BoundSocket), error);
+
+    let error = adapter.receive(&mut server, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::BoundSocket), error);
+
+    assert!(!adapter.is_connected(&server).unwrap());
+}
+
+#[test]
+fn test_close_stops_server() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let server = listen(&mut adapter, 80);
+
+    adapter.client.reset_captured_commands();
+    adapter.client.add_ok_response();
+    adapter.close(server).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPSERVER=0,0\r\n".to_string(), commands[0]);
+
+    // Remote connections are no longer treated as incoming
+    adapter.client.add_urc_message(b"3,CONNECT\r\n");
+    adapter.process_urc_messages();
+    assert!(adapter.is_connected(&Socket::new(3)).unwrap());
+}
+
+#[test]
+fn test_close_bound_socket_without_server() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+    adapter.bind(&mut socket, 80).unwrap();
+
+    adapter.client.reset_captured_commands();
+    adapter.close(socket).unwrap();
+
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+/// Helper for starting a server on the given port
+fn listen(adapter: &mut AdapterType, port: u16) -> Socket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+    // Receiving mode, max. connections and server command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter.bind(&mut socket, port).unwrap();
+    adapter.listen(&mut socket).unwrap();
+
+    socket
+}
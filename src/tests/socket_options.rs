@@ -0,0 +1,187 @@
+use crate::stack::{Error, Socket, SocketOptions};
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::tls::TlsConfig;
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_connect_applies_socket_options() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let options = SocketOptions {
+        linger: Some(5),
+        no_delay: true,
+        send_timeout_ms: 2_000,
+        keep_alive: Some(120),
+    };
+    connect_socket(&mut adapter, options);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CIPMUX=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPTCPOPT=0,5,1,2000\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPRECVMODE=1\r\n".to_string(), commands[2]);
+    assert_eq!(
+        "AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000,120\r\n".to_string(),
+        commands[3]
+    );
+}
+
+#[test]
+fn test_connect_default_socket_options() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    connect_socket(&mut adapter, SocketOptions::default());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CIPTCPOPT=0,-1,0,0\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n".to_string(), commands[3]);
+}
+
+#[test]
+fn test_set_socket_options_already_connected() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter, SocketOptions::default());
+
+    let error = adapter.set_socket_options(&socket, SocketOptions::default()).unwrap_err();
+    assert_eq!(Error::AlreadyConnected, error);
+}
+
+#[test]
+fn test_set_socket_options_invalid_keep_alive() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = adapter.socket().unwrap();
+
+    for keep_alive in [0, 7201] {
+        let options = SocketOptions {
+            keep_alive: Some(keep_alive),
+            ..SocketOptions::default()
+        };
+        let error = adapter.set_socket_options(&socket, options).unwrap_err();
+        assert_eq!(Error::InvalidSocketOptions, error);
+    }
+}
+
+#[test]
+fn test_connect_socket_options_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+    adapter.set_socket_options(&socket, SocketOptions::default()).unwrap();
+
+    let error = adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SocketOptionsError(AtError::Parse)), error);
+}
+
+#[test]
+fn test_socket_options_discarded_on_reuse() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let options = SocketOptions {
+        keep_alive: Some(60),
+        ..SocketOptions::default()
+    };
+    let socket = connect_socket(&mut adapter, options);
+
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_closed();
+    adapter.close(socket).unwrap();
+    adapter.client.reset_captured_commands();
+
+    // Just the connect command, as configuration is already done
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPSTART=0,\"TCP\",\"127.0.0.1\",5000\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_connect_tls_applies_socket_options() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let options = SocketOptions {
+        no_delay: true,
+        keep_alive: Some(30),
+        ..SocketOptions::default()
+    };
+    adapter.set_socket_options(&socket, options).unwrap();
+
+    // SNI, ALPN, PSK, authentication mode, socket options, receiving mode and connect command
+    for _ in 0..7 {
+        adapter.client.add_ok_response();
+    }
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("10.0.0.1:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(8, commands.len());
+    assert_eq!("AT+CIPTCPOPT=0,-1,1,0\r\n".to_string(), commands[5]);
+    assert_eq!("AT+CIPSTART=0,\"SSL\",\"10.0.0.1\",443,30\r\n".to_string(), commands[7]);
+}
+
+/// Helper for opening & connecting a socket with the given options
+fn connect_socket(adapter: &mut AdapterType, options: SocketOptions) -> Socket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+    let mut socket = adapter.socket().unwrap();
+    adapter.set_socket_options(&socket, options).unwrap();
+
+    // Socket options, receiving mode and connect command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+
+    socket
+}
@@ -0,0 +1,315 @@
+use crate::soft_ap::{SoftApConfig, SoftApError, StationEvent};
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::wifi::{Adapter, Encryption, WifiAdapter};
+use alloc::string::ToString;
+use atat::Error;
+use core::str::FromStr;
+use embedded_nal::IpAddr;
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 64>;
+
+#[test]
+fn test_start_soft_ap_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_soft_ap(&config()).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CWMODE=2\r\n".to_string(), commands[0]);
+    assert_eq!(
+        "AT+CWSAP=\"setup_device\",\"secret_password\",6,3,4,1\r\n".to_string(),
+        commands[1]
+    );
+}
+
+#[test]
+fn test_start_soft_ap_open_network() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let config = SoftApConfig {
+        ssid: "open_network",
+        password: "ignored",
+        encryption: Encryption::Open,
+        ..SoftApConfig::default()
+    };
+    adapter.start_soft_ap(&config).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!("AT+CWSAP=\"open_network\",\"\",1,0,10,0\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_start_soft_ap_after_join() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // Join
+    client.add_ok_response();
+    client.add_ok_response();
+    // SoftAP
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.join("test_wifi", "secret").unwrap();
+    adapter.start_soft_ap(&config()).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CWMODE=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CWMODE=3\r\n".to_string(), commands[2]);
+}
+
+#[test]
+fn test_join_keeps_soft_ap_running() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // SoftAP
+    client.add_ok_response();
+    client.add_ok_response();
+    // Join
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_soft_ap(&config()).unwrap();
+    adapter.join("test_wifi", "secret").unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CWMODE=2\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CWMODE=3\r\n".to_string(), commands[2]);
+}
+
+#[test]
+fn test_start_soft_ap_invalid_config() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let invalid = [
+        (SoftApConfig { ssid: "", ..config() }, SoftApError::InvalidSsidLength),
+        (
+            SoftApConfig {
+                ssid: "0123456789abcdef0123456789abcdef0",
+                ..config()
+            },
+            SoftApError::InvalidSsidLength,
+        ),
+        (
+            SoftApConfig {
+                password: "short",
+                ..config()
+            },
+            SoftApError::InvalidPasswordLength,
+        ),
+        (
+            SoftApConfig {
+                channel: 14,
+                ..config()
+            },
+            SoftApError::InvalidChannel,
+        ),
+        (
+            SoftApConfig {
+                encryption: Encryption::Wep,
+                ..config()
+            },
+            SoftApError::InvalidEncryption,
+        ),
+        (
+            SoftApConfig {
+                max_connections: 0,
+                ..config()
+            },
+            SoftApError::InvalidMaxConnections,
+        ),
+    ];
+
+    for (config, error) in invalid {
+        assert_eq!(error, adapter.start_soft_ap(&config).unwrap_err());
+    }
+
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_start_soft_ap_mode_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.start_soft_ap(&config()).unwrap_err();
+    assert_eq!(SoftApError::ModeError(Error::Parse), error);
+}
+
+#[test]
+fn test_start_soft_ap_mode_would_block() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.client.send_would_block(0);
+
+    let error = adapter.start_soft_ap(&config()).unwrap_err();
+    assert_eq!(SoftApError::UnexpectedWouldBlock, error);
+}
+
+#[test]
+fn test_start_soft_ap_configuration_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.start_soft_ap(&config()).unwrap_err();
+    assert_eq!(SoftApError::ConfigurationError(Error::Parse), error);
+}
+
+#[test]
+fn test_stop_soft_ap() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_soft_ap(&config()).unwrap();
+    adapter.stop_soft_ap().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("AT+CWMODE=0\r\n".to_string(), commands[2]);
+}
+
+#[test]
+fn test_stop_soft_ap_keeps_station_mode() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // Join
+    client.add_ok_response();
+    client.add_ok_response();
+    // SoftAP
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.join("test_wifi", "secret").unwrap();
+    adapter.start_soft_ap(&config()).unwrap();
+    adapter.stop_soft_ap().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(5, commands.len());
+    assert_eq!("AT+CWMODE=1\r\n".to_string(), commands[4]);
+}
+
+#[test]
+fn test_get_stations() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_response(b"+CWLIF:192.168.4.2,18:fe:34:a2:b1:09\r\n+CWLIF:192.168.4.3,18:fe:34:a2:b1:0a\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let stations = adapter.get_stations().unwrap();
+
+    assert_eq!(2, stations.len());
+    assert_eq!(IpAddr::from_str("192.168.4.2").unwrap(), stations[0].ip);
+    assert_eq!("18:fe:34:a2:b1:09", stations[0].mac.as_str());
+    assert_eq!(IpAddr::from_str("192.168.4.3").unwrap(), stations[1].ip);
+    assert_eq!("18:fe:34:a2:b1:0a", stations[1].mac.as_str());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CWLIF\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_get_stations_empty() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert!(adapter.get_stations().unwrap().is_empty());
+}
+
+#[test]
+fn test_get_stations_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.get_stations().unwrap_err();
+    assert_eq!(SoftApError::StationListError(Error::Parse), error);
+}
+
+#[test]
+fn test_poll_station_events() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_urc_message(b"+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\n");
+    client.add_urc_message(b"+DIST_STA_IP:\"18:fe:34:a2:b1:09\",\"192.168.4.2\"\r\n");
+    client.add_urc_message(b"+STA_DISCONNECTED:\"18:fe:34:a2:b1:09\"\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mac = "18:fe:34:a2:b1:09";
+
+    assert_eq!(Some(StationEvent::Connected(mac.into())), adapter.poll_station_event());
+    assert_eq!(
+        Some(StationEvent::IpAssigned(
+            mac.into(),
+            IpAddr::from_str("192.168.4.2").unwrap()
+        )),
+        adapter.poll_station_event()
+    );
+    assert_eq!(
+        Some(StationEvent::Disconnected(mac.into())),
+        adapter.poll_station_event()
+    );
+    assert_eq!(None, adapter.poll_station_event());
+}
+
+#[test]
+fn test_poll_station_events_drops_oldest() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_urc_message(b"+STA_CONNECTED:\"18:fe:34:a2:b1:00\"\r\n");
+    for _ in 0..8 {
+        client.add_urc_message(b"+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\n");
+    }
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    for _ in 0..8 {
+        assert_eq!(
+            Some(StationEvent::Connected("18:fe:34:a2:b1:09".into())),
+            adapter.poll_station_event()
+        );
+    }
+    assert_eq!(None, adapter.poll_station_event());
+}
+
+/// Valid SoftAP configuration
+fn config() -> SoftApConfig<'static> {
+    SoftApConfig {
+        ssid: "setup_device",
+        password: "secret_password",
+        channel: 6,
+        encryption: Encryption::Wpa2Psk,
+        max_connections: 4,
+        hidden: true,
+    }
+}
@@ -1,6 +1,6 @@
-use crate::stack::{Error, Socket};
+use crate::stack::{Error, PendingSend, Socket};
 use crate::tests::mock::{MockAtatClient, MockTimer};
-use crate::wifi::Adapter;
+use crate::wifi::{Adapter, JoinError, WifiAdapter};
 use alloc::string::{String, ToString};
 use alloc::vec;
 use atat::Error as AtError;
@@ -80,6 +80,21 @@ fn test_socket_not_available() {
     assert_eq!(Error::NoSocketAvailable, result);
 }
 
+#[test]
+fn test_socket_custom_socket_count() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4, 8> = Adapter::new(client, timer);
+    for link_id in 0..8 {
+        assert_eq!(link_id, adapter.socket().unwrap().link_id);
+    }
+
+    let result = adapter.socket().unwrap_err();
+    assert_eq!(Error::NoSocketAvailable, result);
+}
+
 #[test]
 fn test_connect_already_connected_by_urc() {
     let timer = MockTimer::new();
@@ -496,11 +511,13 @@ fn test_send_tx_command_would_block() {
 }
 
 #[test]
-fn test_send_timer_start_error() {
+fn test_send_timeout() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|duration| {
-        assert_eq!(duration, MockTimer::duration_ms(5_000));
-        Err(100)
+    let mut now = 0;
+    timer.expect_now().times(2).returning(move || {
+        let instant = MockTimer::instant_ms(now);
+        now += 5_000;
+        instant
     });
 
     let client = MockAtatClient::new();
@@ -512,66 +529,43 @@ fn test_send_timer_start_error() {
     // Actual TX command
     adapter.client.add_ok_response();
 
-    let error = adapter.send(&mut socket, b"test data").unwrap_err();
-    assert_eq!(nb::Error::Other(Error::TimerError), error);
-}
-
-#[test]
-fn test_send_timer_wait_error() {
-    let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|duration| {
-        assert_eq!(duration, MockTimer::duration_ms(5_000));
-        Ok(())
-    });
-
-    timer
-        .expect_wait()
-        .times(1)
-        .returning(|| nb::Result::Err(nb::Error::Other(100)));
-
-    let client = MockAtatClient::new();
-    let mut adapter: AdapterType = Adapter::new(client, timer);
-    let mut socket = connect_socket(&mut adapter);
-
-    // TX prepare command
-    adapter.client.add_ok_response();
-    // Actual TX command
-    adapter.client.add_ok_response();
+    adapter.client.expect_reset_calls();
 
     let error = adapter.send(&mut socket, b"test data").unwrap_err();
-    assert_eq!(nb::Error::Other(Error::TimerError), error);
+    assert_eq!(nb::Error::Other(Error::SendFailed(AtError::Timeout)), error);
+    assert_eq!(1, adapter.client.get_reset_call_count());
+    assert_eq!(None, adapter.pending_send);
 }
 
 #[test]
-fn test_send_timeout() {
+fn test_send_timeout_independent_of_shared_timer() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|duration| {
-        assert_eq!(duration, MockTimer::duration_ms(5_000));
-        Ok(())
+    let mut now = 0;
+    timer.expect_now().times(3).returning(move || {
+        let instant = MockTimer::instant_ms(now);
+        now += 2_500;
+        instant
     });
 
-    timer.expect_wait().times(1).returning(|| nb::Result::Ok(()));
-
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
     let mut socket = connect_socket(&mut adapter);
 
-    // TX prepare command
     adapter.client.add_ok_response();
-    // Actual TX command
     adapter.client.add_ok_response();
 
-    adapter.client.expect_reset_calls();
+    // Shared timer is neither started nor awaited, so other blocking operations do not affect the deadline
+    assert_eq!(nb::Error::WouldBlock, adapter.send(&mut socket, b"test").unwrap_err());
 
-    let error = adapter.send(&mut socket, b"test data").unwrap_err();
+    adapter.client.expect_reset_calls();
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
     assert_eq!(nb::Error::Other(Error::SendFailed(AtError::Timeout)), error);
-    assert_eq!(1, adapter.client.get_reset_call_count());
 }
 
 #[test]
 fn test_send_byte_count_not_matching() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -593,7 +587,7 @@ fn test_send_byte_count_not_matching() {
 #[test]
 fn test_send_ok_without_recv_message() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -613,7 +607,7 @@ fn test_send_ok_without_recv_message() {
 #[test]
 fn test_send_fail_urc_message() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -637,7 +631,7 @@ fn test_send_fail_urc_message() {
 #[test]
 fn test_send_error_and_recv_bytes_not_matching() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -661,7 +655,7 @@ fn test_send_error_and_recv_bytes_not_matching() {
 #[test]
 fn test_send_correct_commands() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(1).returning(|_| Ok(()));
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -688,7 +682,7 @@ fn test_send_correct_commands() {
 #[test]
 fn test_send_multiple_calls_urc_status_reset() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(2).returning(|_| Ok(()));
+    timer.expect_now().times(2).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -721,7 +715,7 @@ fn test_send_multiple_calls_urc_status_reset() {
 #[test]
 fn test_send_chunks() {
     let mut timer = MockTimer::new();
-    timer.expect_start().times(2).returning(|_| Ok(()));
+    timer.expect_now().times(2).returning(|| MockTimer::instant_ms(0));
 
     let client = MockAtatClient::new();
     let mut adapter: AdapterType = Adapter::new(client, timer);
@@ -756,6 +750,257 @@ fn test_send_chunks() {
     assert_eq!("second message".to_string(), commands[6]);
 }
 
+#[test]
+fn test_send_would_block_until_confirmed() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(3).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    // TX prepare command
+    adapter.client.add_ok_response();
+    // Actual TX command
+    adapter.client.add_ok_response();
+
+    assert_eq!(nb::Error::WouldBlock, adapter.send(&mut socket, b"test").unwrap_err());
+    assert_eq!(nb::Error::WouldBlock, adapter.send(&mut socket, b"test").unwrap_err());
+
+    adapter.client.add_urc_send_ok();
+    assert_eq!(4, adapter.send(&mut socket, b"test").unwrap());
+
+    // Data is not transmitted again when resuming
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(5, commands.len());
+    assert_eq!("AT+CIPSEND=0,4\r\n".to_string(), commands[3]);
+    assert_eq!("test".to_string(), commands[4]);
+    assert_eq!(None, adapter.pending_send);
+}
+
+#[test]
+fn test_send_partial_progress() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(3).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+
+    let mut buffer = vec![b'A'; 256];
+    buffer.extend_from_slice(b"second message");
+
+    // Just the first block is confirmed
+    assert_eq!(256, adapter.send(&mut socket, buffer.as_slice()).unwrap());
+
+    adapter.client.add_urc_send_ok();
+    assert_eq!(14, adapter.send(&mut socket, &buffer[256..]).unwrap());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(7, commands.len());
+    assert_eq!("AT+CIPSEND=0,14\r\n".to_string(), commands[5]);
+    assert_eq!("second message".to_string(), commands[6]);
+}
+
+#[test]
+fn test_send_pending_transmission_of_other_socket() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+    adapter.client.reset_captured_commands();
+
+    adapter.pending_send = Some(PendingSend {
+        link_id: 1,
+        length: 4,
+        deadline: MockTimer::instant_ms(5_000),
+    });
+
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_commands_while_send_pending() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+    let mut unconnected = adapter.socket().unwrap();
+    adapter.client.reset_captured_commands();
+
+    adapter.pending_send = Some(PendingSend {
+        link_id: 3,
+        length: 4,
+        deadline: MockTimer::instant_ms(5_000),
+    });
+
+    let mut buffer = [0x0; 4];
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter.receive(&mut socket, &mut buffer).unwrap_err()
+    );
+    assert_eq!(
+        nb::Error::WouldBlock,
+        adapter
+            .connect(&mut unconnected, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+            .unwrap_err()
+    );
+    assert_eq!(JoinError::SendPending, adapter.join("test_wifi", "secret").unwrap_err());
+    assert_eq!(Error::SendPending, adapter.resync_connections().unwrap_err());
+    assert_eq!(Error::SendPending, adapter.close(socket).unwrap_err());
+
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_send_resumed_buffer_shorter_than_pending() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(2).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    assert_eq!(nb::Error::WouldBlock, adapter.send(&mut socket, b"test").unwrap_err());
+
+    let error = adapter.send(&mut socket, b"te").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::PartialSend), error);
+    assert_eq!(None, adapter.pending_send);
+}
+
+#[test]
+fn test_close_discards_pending_transmission() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(2).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    assert_eq!(nb::Error::WouldBlock, adapter.send(&mut socket, b"test").unwrap_err());
+
+    // Socket was closed remotely
+    adapter.client.add_urc_message(b"0,CLOSED\r\n");
+    adapter.close(socket).unwrap();
+
+    assert_eq!(None, adapter.pending_send);
+}
+
+#[test]
+fn test_send_long_transmission_correct_commands() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_long_transmission(true);
+    let mut socket = connect_socket(&mut adapter);
+
+    // Long TX prepare command
+    adapter.client.add_ok_response();
+    // Actual TX commands
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_message(b"Recv 270 bytes\r\n");
+    adapter.client.add_urc_send_ok();
+
+    let mut buffer = vec![b'A'; 256];
+    buffer.extend_from_slice(b"second message");
+
+    let sent_bytes = adapter.send(&mut socket, buffer.as_slice()).unwrap();
+    assert_eq!(270, sent_bytes);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(6, commands.len());
+    assert_eq!("AT+CIPSENDL=0,270\r\n".to_string(), commands[3]);
+    assert_eq!(String::from_utf8(vec![b'A'; 256]).unwrap(), commands[4]);
+    assert_eq!("second message".to_string(), commands[5]);
+}
+
+#[test]
+fn test_send_long_transmission_progress_restarts_timeout() {
+    let mut timer = MockTimer::new();
+    let mut instants = [0, 4_000, 6_000, 9_000].into_iter();
+    timer
+        .expect_now()
+        .times(4)
+        .returning(move || MockTimer::instant_ms(instants.next().unwrap()));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_long_transmission(true);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_message(b"+CIPSENDL:4,2\r\n");
+    adapter.client.expect_reset_calls();
+
+    // Timeout is restarted by the progress message
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+
+    // Initial deadline has passed
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SendFailed(AtError::Timeout)), error);
+}
+
+#[test]
+fn test_send_long_transmission_prepare_error() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_long_transmission(true);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_error_response();
+
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::TransmissionStartFailed(AtError::Parse)), error);
+}
+
+#[test]
+fn test_send_long_transmission_fail_urc_message() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.set_long_transmission(true);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_fail();
+    adapter.client.expect_reset_calls();
+
+    let error = adapter.send(&mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SendFailed(AtError::Error)), error);
+}
+
 #[test]
 fn test_receive_no_data_available() {
     let timer = MockTimer::new();
@@ -773,6 +1018,60 @@ fn test_receive_no_data_available() {
     assert_eq!(nb::Error::WouldBlock, error);
 }
 
+#[test]
+fn test_receive_multi_digit_link_id() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    // Receiving sockets
+    client.add_ok_response();
+
+    let mut adapter: Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4, 12> = Adapter::new(client, timer);
+    for _ in 0..11 {
+        adapter.socket().unwrap();
+    }
+    let mut socket = adapter.socket().unwrap();
+    assert_eq!(11, socket.link_id);
+
+    // Connecting socket
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_message(b"11,CONNECT\r\n");
+    adapter
+        .connect(&mut socket, SocketAddr::from_str("127.0.0.1:5000").unwrap())
+        .unwrap();
+
+    adapter.client.add_urc_message(b"+IPD,11,4\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,4:aaaa");
+
+    let mut buffer = [b' '; 16];
+    assert_eq!(4, adapter.receive(&mut socket, &mut buffer).unwrap());
+    assert_eq!(b"aaaa", &buffer[..4]);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!("AT+CIPRECVDATA=11,4\r\n".to_string(), commands[3]);
+}
+
+#[test]
+fn test_receive_unsupported_link_id() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter);
+
+    adapter.client.add_urc_message(b"+IPD,5,256\r\n");
+
+    let mut buffer = [0x0; 32];
+    let error = adapter.receive(&mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::UnsupportedLinkId(5)), error);
+
+    // Error is just reported once
+    let error = adapter.receive(&mut socket, &mut buffer).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
 #[test]
 fn test_receive_receive_command_failed() {
     let timer = MockTimer::new();
@@ -0,0 +1,423 @@
+use crate::stack::{Error, Socket};
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::tls::{AuthMode, Credential, Psk, TlsConfig};
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 4>;
+
+#[test]
+fn test_connect_tls_correct_commands_ipv4() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let config = TlsConfig {
+        server_name: Some("example.org"),
+        alpn: &["h2", "http/1.1"],
+        auth_mode: AuthMode::ServerVerification,
+        ..TlsConfig::default()
+    };
+    connect_socket(&mut adapter, "10.0.0.1:443", &config);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(7, commands.len());
+    assert_eq!("AT+CIPMUX=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPSSLCSNI=0,\"example.org\"\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPSSLCALPN=0,2,\"h2\",\"http/1.1\"\r\n".to_string(), commands[2]);
+    assert_eq!("AT+CIPSSLCPSK=0,\"\",\"\"\r\n".to_string(), commands[3]);
+    assert_eq!("AT+CIPSSLCCONF=0,2,0,0\r\n".to_string(), commands[4]);
+    assert_eq!("AT+CIPRECVMODE=1\r\n".to_string(), commands[5]);
+    assert_eq!("AT+CIPSTART=0,\"SSL\",\"10.0.0.1\",443\r\n".to_string(), commands[6]);
+}
+
+#[test]
+fn test_connect_tls_correct_commands_ipv6() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    connect_socket(&mut adapter, "[2001:db8::1]:443", &TlsConfig::default());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(7, commands.len());
+    assert_eq!("AT+CIPSSLCCONF=0,0,0,0\r\n".to_string(), commands[4]);
+    assert_eq!(
+        "AT+CIPSTART=0,\"SSLv6\",\"2001:0db8:0:0:0:0:0:0001\",443\r\n".to_string(),
+        commands[6]
+    );
+}
+
+#[test]
+fn test_connect_tls_auth_modes() {
+    let modes = [
+        (AuthMode::None, "AT+CIPSSLCCONF=0,0,0,0\r\n"),
+        (AuthMode::ClientCertificate, "AT+CIPSSLCCONF=0,1,0,0\r\n"),
+        (AuthMode::ServerVerification, "AT+CIPSSLCCONF=0,2,0,0\r\n"),
+        (AuthMode::Mutual, "AT+CIPSSLCCONF=0,3,0,0\r\n"),
+    ];
+
+    for (auth_mode, expected) in modes {
+        let timer = MockTimer::new();
+        let client = MockAtatClient::new();
+        let mut adapter: AdapterType = Adapter::new(client, timer);
+
+        let config = TlsConfig {
+            auth_mode,
+            ..TlsConfig::default()
+        };
+        connect_socket(&mut adapter, "10.0.0.1:443", &config);
+
+        let commands = adapter.client.get_commands_as_strings();
+        assert_eq!(expected.to_string(), commands[4]);
+    }
+}
+
+#[test]
+fn test_connect_tls_reused_link_cleared() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let config = TlsConfig {
+        server_name: Some("example.org"),
+        alpn: &["h2"],
+        psk: Some(Psk {
+            key: "identity",
+            hint: "hint",
+        }),
+        ..TlsConfig::default()
+    };
+    let socket = connect_socket(&mut adapter, "10.0.0.1:443", &config);
+
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_closed();
+    adapter.close(socket).unwrap();
+    adapter.client.reset_captured_commands();
+
+    // Link 0 is reused, previous configuration must not leak into the new connection
+    for _ in 0..4 {
+        adapter.client.add_ok_response();
+    }
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("10.0.0.2:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap();
+    assert_eq!(0, socket.link_id);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(5, commands.len());
+    assert_eq!("AT+CIPSSLCSNI=0,\"\"\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPSSLCALPN=0,0\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPSSLCPSK=0,\"\",\"\"\r\n".to_string(), commands[2]);
+    assert_eq!("AT+CIPSSLCCONF=0,0,0,0\r\n".to_string(), commands[3]);
+    assert_eq!("AT+CIPSTART=0,\"SSL\",\"10.0.0.2\",443\r\n".to_string(), commands[4]);
+}
+
+#[test]
+fn test_connect_tls_configuration_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Multiple connections command
+    client.add_ok_response();
+    // SNI command
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let config = TlsConfig {
+        server_name: Some("example.org"),
+        ..TlsConfig::default()
+    };
+    let error = adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("10.0.0.1:443").unwrap(), &config)
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::TlsConfigurationError(AtError::Parse)), error);
+}
+
+#[test]
+fn test_connect_tls_invalid_configuration() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+    let remote = SocketAddr::from_str("10.0.0.1:443").unwrap();
+
+    let server_name = "a".repeat(65);
+    let config = TlsConfig {
+        server_name: Some(server_name.as_str()),
+        ..TlsConfig::default()
+    };
+    let error = adapter.connect_tls(&mut socket, remote, &config).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::InvalidTlsConfiguration), error);
+
+    let protocol = "a".repeat(33);
+    let config = TlsConfig {
+        alpn: &[protocol.as_str()],
+        ..TlsConfig::default()
+    };
+    let error = adapter.connect_tls(&mut socket, remote, &config).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::InvalidTlsConfiguration), error);
+
+    let config = TlsConfig {
+        alpn: &["a", "b", "c", "d"],
+        ..TlsConfig::default()
+    };
+    let error = adapter.connect_tls(&mut socket, remote, &config).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::InvalidTlsConfiguration), error);
+}
+
+#[test]
+fn test_connect_tls_already_connected() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:443", &TlsConfig::default());
+    let error = adapter
+        .connect_tls(
+            &mut socket,
+            SocketAddr::from_str("10.0.0.1:443").unwrap(),
+            &TlsConfig::default(),
+        )
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::AlreadyConnected), error);
+}
+
+#[test]
+fn test_send_over_tls_connection() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:443", &TlsConfig::default());
+
+    adapter.client.reset_captured_commands();
+
+    // TX prepare command
+    adapter.client.add_ok_response();
+    // Actual TX command
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+
+    assert_eq!(4, adapter.send(&mut socket, b"test").unwrap());
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!("AT+CIPSEND=0,4\r\n".to_string(), commands[0]);
+    assert_eq!("test".to_string(), commands[1]);
+}
+
+#[test]
+fn test_connect_tls_psk_and_indexes() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let config = TlsConfig {
+        psk: Some(Psk {
+            key: "identity",
+            hint: "hint",
+        }),
+        auth_mode: AuthMode::Mutual,
+        certificate_index: 1,
+        ca_index: 2,
+        ..TlsConfig::default()
+    };
+    connect_socket(&mut adapter, "10.0.0.1:443", &config);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!("AT+CIPSSLCPSK=0,\"identity\",\"hint\"\r\n".to_string(), commands[3]);
+    assert_eq!("AT+CIPSSLCCONF=0,3,1,2\r\n".to_string(), commands[4]);
+}
+
+#[test]
+fn test_connect_tls_invalid_psk() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = adapter.socket().unwrap();
+
+    let key = "a".repeat(33);
+    let config = TlsConfig {
+        psk: Some(Psk {
+            key: key.as_str(),
+            hint: "hint",
+        }),
+        ..TlsConfig::default()
+    };
+    let error = adapter
+        .connect_tls(&mut socket, SocketAddr::from_str("10.0.0.1:443").unwrap(), &config)
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::InvalidTlsConfiguration), error);
+}
+
+#[test]
+fn test_write_credential_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Write commands + data of each credential
+    for _ in 0..6 {
+        client.add_ok_response();
+    }
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.write_credential(Credential::CaCertificate, 0, b"ca").unwrap();
+    adapter.write_credential(Credential::ClientCertificate, 1, b"cert").unwrap();
+    adapter.write_credential(Credential::ClientKey, 1, b"key").unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(6, commands.len());
+    assert_eq!(
+        "AT+SYSMFG=2,\"client_ca\",\"client_ca.0\",8,2\r\n".to_string(),
+        commands[0]
+    );
+    assert_eq!("ca".to_string(), commands[1]);
+    assert_eq!(
+        "AT+SYSMFG=2,\"client_cert\",\"client_cert.1\",8,4\r\n".to_string(),
+        commands[2]
+    );
+    assert_eq!("cert".to_string(), commands[3]);
+    assert_eq!(
+        "AT+SYSMFG=2,\"client_key\",\"client_key.1\",8,3\r\n".to_string(),
+        commands[4]
+    );
+    assert_eq!("key".to_string(), commands[5]);
+}
+
+#[test]
+fn test_write_credential_chunked() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Write command + 3 chunks
+    for _ in 0..4 {
+        client.add_ok_response();
+    }
+
+    let mut adapter: Adapter<MockAtatClient, MockTimer, 1_000_000, 4, 4> = Adapter::new(client, timer);
+    adapter
+        .write_credential(Credential::ClientCertificate, 0, b"0123456789")
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!(
+        "AT+SYSMFG=2,\"client_cert\",\"client_cert.0\",8,10\r\n".to_string(),
+        commands[0]
+    );
+    assert_eq!("0123".to_string(), commands[1]);
+    assert_eq!("4567".to_string(), commands[2]);
+    assert_eq!("89".to_string(), commands[3]);
+}
+
+#[test]
+fn test_write_credential_upload_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Write command
+    client.add_ok_response();
+    // Data
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.write_credential(Credential::ClientKey, 0, b"key").unwrap_err();
+    assert_eq!(Error::CredentialWriteError(AtError::Parse), error);
+}
+
+#[test]
+fn test_write_credential_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.write_credential(Credential::ClientKey, 0, b"key").unwrap_err();
+    assert_eq!(Error::CredentialWriteError(AtError::Parse), error);
+    assert_eq!(1, adapter.client.get_commands_as_strings().len());
+}
+
+#[test]
+fn test_write_credential_empty() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(
+        Error::InvalidCredential,
+        adapter.write_credential(Credential::ClientKey, 0, b"").unwrap_err()
+    );
+    assert_eq!(
+        Error::InvalidCredential,
+        adapter.write_credential_partition(Credential::ClientKey, b"").unwrap_err()
+    );
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_write_credential_partition_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Erase + write command + data
+    for _ in 0..3 {
+        client.add_ok_response();
+    }
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter
+        .write_credential_partition(Credential::ClientCertificate, b"data")
+        .unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("AT+SYSFLASH=0,\"client_cert\"\r\n".to_string(), commands[0]);
+    assert_eq!("AT+SYSFLASH=1,\"client_cert\",0,4\r\n".to_string(), commands[1]);
+    assert_eq!("data".to_string(), commands[2]);
+}
+
+/// Helper for opening & connecting a TLS socket
+fn connect_socket(adapter: &mut AdapterType, remote: &str, config: &TlsConfig) -> Socket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+
+    // SNI, ALPN, PSK and authentication mode commands
+    for _ in 0..4 {
+        adapter.client.add_ok_response();
+    }
+
+    // Receiving mode + connect command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = adapter.socket().unwrap();
+    adapter
+        .connect_tls(&mut socket, SocketAddr::from_str(remote).unwrap(), config)
+        .unwrap();
+
+    socket
+}
@@ -0,0 +1,608 @@
+use crate::stack::Error;
+use crate::tests::mock::{MockAtatClient, MockTimer};
+use crate::udp::UdpSocket;
+use crate::wifi::Adapter;
+use alloc::string::ToString;
+use atat::Error as AtError;
+use core::str::FromStr;
+use embedded_nal::{SocketAddr, TcpClientStack, UdpClientStack, UdpFullStack};
+
+type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 8, 4>;
+
+#[test]
+fn test_socket_shares_link_ids_with_tcp() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(0, TcpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(1, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(2, TcpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(3, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+    assert_eq!(4, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+
+    let error = UdpClientStack::socket(&mut adapter).unwrap_err();
+    assert_eq!(Error::NoSocketAvailable, error);
+}
+
+#[test]
+fn test_connect_correct_commands_ipv4() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    connect_socket(&mut adapter, "10.0.0.1:53");
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!("AT+CIPMUX=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPRECVMODE=1\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPSTART=0,\"UDP\",\"10.0.0.1\",53\r\n".to_string(), commands[2]);
+}
+
+#[test]
+fn test_connect_correct_commands_ipv6() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    connect_socket(&mut adapter, "[2001:db8::1]:123");
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(3, commands.len());
+    assert_eq!(
+        "AT+CIPSTART=0,\"UDPv6\",\"2001:0db8:0:0:0:0:0:0001\",123\r\n".to_string(),
+        commands[2]
+    );
+}
+
+#[test]
+fn test_connect_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Multiple connections command
+    client.add_ok_response();
+    // Receiving mode command
+    client.add_ok_response();
+    // Connect command
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    let error =
+        UdpClientStack::connect(&mut adapter, &mut socket, SocketAddr::from_str("10.0.0.1:53").unwrap()).unwrap_err();
+
+    assert_eq!(Error::ConnectError(AtError::Parse), error);
+}
+
+#[test]
+fn test_connect_already_connected() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+    let error =
+        UdpClientStack::connect(&mut adapter, &mut socket, SocketAddr::from_str("10.0.0.1:54").unwrap()).unwrap_err();
+
+    assert_eq!(Error::AlreadyConnected, error);
+}
+
+#[test]
+fn test_send_correct_commands() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    // TX prepare command
+    adapter.client.add_ok_response();
+    // Actual TX command
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+
+    UdpClientStack::send(&mut adapter, &mut socket, b"ping").unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(5, commands.len());
+    assert_eq!("AT+CIPSEND=0,4\r\n".to_string(), commands[3]);
+    assert_eq!("ping".to_string(), commands[4]);
+}
+
+#[test]
+fn test_send_would_block_until_confirmed() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(2).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"ping").unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+
+    adapter.client.add_urc_send_ok();
+    UdpClientStack::send(&mut adapter, &mut socket, b"ping").unwrap();
+
+    // Datagram is not transmitted again when resuming
+    assert_eq!(5, adapter.client.get_commands_as_strings().len());
+}
+
+#[test]
+fn test_send_datagram_too_large() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.reset_captured_commands();
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"too large").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::DatagramTooLarge), error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_send_not_connected() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"ping").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::SocketUnconnected), error);
+}
+
+#[test]
+fn test_receive_no_data_available() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    let error = UdpClientStack::receive(&mut adapter, &mut socket, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::WouldBlock, error);
+}
+
+#[test]
+fn test_receive_remote_of_connect() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.add_urc_message(b"+IPD,0,3\r\n");
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+
+    let mut buffer = [b' '; 4];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(3, length);
+    assert_eq!(b"abc ", &buffer);
+    assert_eq!(SocketAddr::from_str("10.0.0.1:53").unwrap(), remote);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[3]);
+}
+
+#[test]
+fn test_receive_remote_of_data_available_message() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.add_urc_message(b"+IPD,0,3,\"10.0.0.2\",5353\r\n");
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+
+    let (_, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut [0x0; 4]).unwrap();
+    assert_eq!(SocketAddr::from_str("10.0.0.2:5353").unwrap(), remote);
+}
+
+#[test]
+fn test_receive_remote_of_data_message() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.add_urc_message(b"+IPD,0,3,\"10.0.0.2\",5353\r\n");
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3,\"2001:db8::1\",6000:abc");
+
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut [0x0; 4]).unwrap();
+    assert_eq!(3, length);
+    assert_eq!(SocketAddr::from_str("[2001:db8::1]:6000").unwrap(), remote);
+}
+
+#[test]
+fn test_receive_single_block_per_datagram() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.add_urc_message(b"+IPD,0,6\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:def");
+
+    let mut buffer = [b' '; 16];
+    let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(3, length);
+    assert_eq!(b"abc", &buffer[..3]);
+
+    let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(3, length);
+    assert_eq!(b"def", &buffer[..3]);
+}
+
+#[test]
+fn test_receive_oversized_datagram_truncated() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_urc_message(b"+IPD,0,6\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,4:abcd");
+    adapter.client.add_urc_message(b"+CIPRECVDATA,2:ef");
+
+    let mut buffer = [b' '; 2];
+    let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(2, length);
+    assert_eq!(b"ab", &buffer);
+
+    // Next call returns the remainder of the datagram exceeding RX_SIZE
+    let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(2, length);
+    assert_eq!(b"ef", &buffer);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_receive_datagram_larger_than_rx_size() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_urc_message(b"+IPD,0,6\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,4:abcd");
+    adapter.client.add_urc_message(b"+CIPRECVDATA,2:ef");
+
+    let mut buffer = [b' '; 16];
+    let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(4, length);
+    assert_eq!(b"abcd", &buffer[..4]);
+    assert_eq!(2, adapter.data_available[0]);
+
+    // Remainder is kept on ESP-AT and returned by the next call
+    let (length, _) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(2, length);
+    assert_eq!(b"ef", &buffer[..2]);
+    assert_eq!(0, adapter.data_available[0]);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPRECVDATA=0,4\r\n".to_string(), commands[1]);
+
+    assert_eq!(
+        nb::Error::WouldBlock,
+        UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap_err()
+    );
+}
+
+#[test]
+fn test_close_correct_commands() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    adapter.client.reset_captured_commands();
+    adapter.client.throttle_urc();
+
+    // Dummy URC for first URC check call
+    adapter.client.add_urc_wifi_got_ip();
+
+    adapter.client.add_ok_response();
+    adapter.client.add_urc_first_socket_closed();
+    UdpClientStack::close(&mut adapter, socket).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CIPCLOSE=0\r\n".to_string(), commands[0]);
+
+    // Socket is available for reuse
+    assert_eq!(0, UdpClientStack::socket(&mut adapter).unwrap().link_id);
+}
+
+#[test]
+fn test_bind_correct_commands() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    bind_socket(&mut adapter, 5353);
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(4, commands.len());
+    assert_eq!("AT+CIPMUX=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CIPDINFO=1\r\n".to_string(), commands[1]);
+    assert_eq!("AT+CIPRECVMODE=1\r\n".to_string(), commands[2]);
+    assert_eq!(
+        "AT+CIPSTART=0,\"UDP\",\"0.0.0.0\",0,5353,2\r\n".to_string(),
+        commands[3]
+    );
+}
+
+#[test]
+fn test_bind_data_info_command_sent_once() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    bind_socket(&mut adapter, 5353);
+    adapter.client.reset_captured_commands();
+
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_second_socket_connected();
+
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+    adapter.bind(&mut socket, 1900).unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!(
+        "AT+CIPSTART=1,\"UDP\",\"0.0.0.0\",0,1900,2\r\n".to_string(),
+        commands[0]
+    );
+}
+
+#[test]
+fn test_bind_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Multiple connections command
+    client.add_ok_response();
+    // Data info + receiving mode command
+    client.add_ok_response();
+    client.add_ok_response();
+    // Bind command
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+
+    let error = adapter.bind(&mut socket, 5353).unwrap_err();
+    assert_eq!(Error::BindError(AtError::Parse), error);
+}
+
+#[test]
+fn test_bind_data_info_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    // Multiple connections command
+    client.add_ok_response();
+    // Data info command
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = UdpClientStack::socket(&mut adapter).unwrap();
+
+    let error = adapter.bind(&mut socket, 5353).unwrap_err();
+    assert_eq!(Error::EnablingDataInfoFailed(AtError::Parse), error);
+}
+
+#[test]
+fn test_bind_already_connected() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+    let error = adapter.bind(&mut socket, 5353).unwrap_err();
+    assert_eq!(Error::AlreadyConnected, error);
+}
+
+#[test]
+fn test_send_to_correct_commands_ipv4() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = bind_socket(&mut adapter, 5353);
+
+    adapter.client.reset_captured_commands();
+    send_to(&mut adapter, &mut socket, "10.0.0.2:5000", b"ping");
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CIPSEND=0,4,\"10.0.0.2\",5000\r\n".to_string(), commands[0]);
+    assert_eq!("ping".to_string(), commands[1]);
+}
+
+#[test]
+fn test_send_to_correct_commands_ipv6() {
+    let mut timer = MockTimer::new();
+    timer.expect_now().times(1).returning(|| MockTimer::instant_ms(0));
+
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = bind_socket(&mut adapter, 5353);
+
+    adapter.client.reset_captured_commands();
+    send_to(&mut adapter, &mut socket, "[2001:db8::1]:5000", b"ping");
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(
+        "AT+CIPSEND=0,4,\"2001:0db8:0:0:0:0:0:0001\",5000\r\n".to_string(),
+        commands[0]
+    );
+}
+
+#[test]
+fn test_send_to_unbound_socket() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = connect_socket(&mut adapter, "10.0.0.1:53");
+
+    let error = adapter
+        .send_to(&mut socket, SocketAddr::from_str("10.0.0.2:5000").unwrap(), b"ping")
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::UnboundSocket), error);
+}
+
+#[test]
+fn test_send_to_datagram_too_large() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = bind_socket(&mut adapter, 5353);
+
+    let error = adapter
+        .send_to(
+            &mut socket,
+            SocketAddr::from_str("10.0.0.2:5000").unwrap(),
+            b"too large",
+        )
+        .unwrap_err();
+    assert_eq!(nb::Error::Other(Error::DatagramTooLarge), error);
+}
+
+#[test]
+fn test_send_bound_socket() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = bind_socket(&mut adapter, 5353);
+
+    let error = UdpClientStack::send(&mut adapter, &mut socket, b"ping").unwrap_err();
+    assert_eq!(nb::Error::Other(Error::BoundSocket), error);
+}
+
+#[test]
+fn test_receive_bound_socket() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = bind_socket(&mut adapter, 5353);
+
+    adapter.client.add_urc_message(b"+IPD,0,3,\"10.0.0.2\",5000\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+
+    let mut buffer = [b' '; 4];
+    let (length, remote) = UdpClientStack::receive(&mut adapter, &mut socket, &mut buffer).unwrap();
+    assert_eq!(3, length);
+    assert_eq!(b"abc ", &buffer);
+    assert_eq!(SocketAddr::from_str("10.0.0.2:5000").unwrap(), remote);
+}
+
+#[test]
+fn test_receive_bound_socket_without_remote() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = bind_socket(&mut adapter, 5353);
+
+    adapter.client.add_urc_message(b"+IPD,0,3\r\n");
+    adapter.client.add_ok_response();
+    adapter.client.throttle_urc();
+    adapter.client.add_urc_message(b"+CIPRECVDATA,3:abc");
+
+    let error = UdpClientStack::receive(&mut adapter, &mut socket, &mut [0x0; 4]).unwrap_err();
+    assert_eq!(nb::Error::Other(Error::ReceiveFailed(AtError::InvalidResponse)), error);
+}
+
+/// Helper for binding a UDP socket to the given local port
+fn bind_socket(adapter: &mut AdapterType, port: u16) -> UdpSocket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+
+    // Data info + receiving mode + bind command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = UdpClientStack::socket(adapter).unwrap();
+    adapter.bind(&mut socket, port).unwrap();
+
+    socket
+}
+
+/// Helper for sending a datagram to the given remote
+fn send_to(adapter: &mut AdapterType, socket: &mut UdpSocket, remote: &str, data: &[u8]) {
+    // TX prepare command
+    adapter.client.add_ok_response();
+    // Actual TX command
+    adapter.client.add_ok_response();
+
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_send_ok();
+
+    adapter.send_to(socket, SocketAddr::from_str(remote).unwrap(), data).unwrap();
+}
+
+/// Helper for opening & connecting a UDP socket
+fn connect_socket(adapter: &mut AdapterType, remote: &str) -> UdpSocket {
+    // Multiple connections command
+    adapter.client.add_ok_response();
+
+    // Receiving mode + connect command
+    adapter.client.add_ok_response();
+    adapter.client.add_ok_response();
+    adapter.client.skip_urc(1);
+    adapter.client.add_urc_first_socket_connected();
+
+    let mut socket = UdpClientStack::socket(adapter).unwrap();
+    UdpClientStack::connect(adapter, &mut socket, SocketAddr::from_str(remote).unwrap()).unwrap();
+
+    socket
+}
@@ -6,7 +6,7 @@ use crate::urc::URCMessages;
 use atat::heapless::String;
 use atat::{AtatCmd, AtatUrc, Parser};
 use core::str::FromStr;
-use embedded_nal::SocketAddrV4;
+use embedded_nal::{IpAddr, SocketAddr, SocketAddrV4};
 use heapless::Vec;
 
 #[test]
@@ -82,6 +82,7 @@ fn test_first_parse_wifi() {
 fn test_first_parse_connection_connected() {
     assert_result(b"0,CONNECT\r\n", 11, b"0,CONNECT\r\nNEXT LINE\r\n");
     assert_result(b"0,CONNECT\r\n", 15, b"\r\n\r\n0,CONNECT\r\n");
+    assert_result(b"12,CONNECT\r\n", 12, b"12,CONNECT\r\nNEXT LINE\r\n");
 }
 
 #[test]
@@ -89,6 +90,13 @@ fn test_first_parse_connection_closed() {
     assert_result(b"0,CLOSED\r\n", 10, b"0,CLOSED\r\nNEXT LINE\r\n");
     assert_result(b"0,CLOSED\r\n", 12, b"\r\n0,CLOSED\r\n");
     assert_result(b"0,CLOSED\r\n", 14, b"\r\n\r\n0,CLOSED\r\n");
+    assert_result(b"12,CLOSED\r\n", 13, b"\r\n12,CLOSED\r\n");
+}
+
+#[test]
+fn test_first_parse_single_connection() {
+    assert_result(b"CONNECT\r\n", 9, b"CONNECT\r\nNEXT LINE\r\n");
+    assert_result(b"CLOSED\r\n", 10, b"\r\nCLOSED\r\n");
 }
 
 #[test]
@@ -97,6 +105,32 @@ fn test_first_parse_already_connected() {
     assert_result(b"ALREADY CONNECTED\r\n", 21, b"\r\nALREADY CONNECTED\r\n\r\n");
 }
 
+#[test]
+fn test_first_parse_station_events() {
+    assert_result(
+        b"+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\n",
+        36,
+        b"+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\nNEXT LINE\r\n",
+    );
+    assert_result(
+        b"+STA_DISCONNECTED:\"18:fe:34:a2:b1:09\"\r\n",
+        41,
+        b"\r\n+STA_DISCONNECTED:\"18:fe:34:a2:b1:09\"\r\n",
+    );
+    assert_result(
+        b"+DIST_STA_IP:\"18:fe:34:a2:b1:09\",\"192.168.4.2\"\r\n",
+        48,
+        b"+DIST_STA_IP:\"18:fe:34:a2:b1:09\",\"192.168.4.2\"\r\n\r\n",
+    );
+}
+
+#[test]
+fn test_first_parse_join_error() {
+    assert_result(b"+CWJAP:2\r\n", 10, b"+CWJAP:2\r\n\r\nERROR\r\n");
+    assert_result(b"+CWJAP:3\r\n", 12, b"\r\n+CWJAP:3\r\n");
+    assert!(<URCMessages<32> as Parser>::parse(b"+CWJAP:\"test_wifi\",\"10:fe:ed:05:ba:51\",6,-45\r\n").is_err());
+}
+
 #[test]
 fn test_first_parse_receive_confirmation() {
     assert_result(b"Recv 9 bytes\r\n", 14, b"Recv 9 bytes\r\n");
@@ -118,6 +152,12 @@ fn test_first_parse_send_fail() {
     assert_result(b"SEND FAIL\r\n", 17, b"\r\n\r\n\r\nSEND FAIL\r\n");
 }
 
+#[test]
+fn test_first_parse_send_progress() {
+    assert_result(b"+CIPSENDL:2048,1024\r\n", 21, b"+CIPSENDL:2048,1024\r\n");
+    assert_result(b"+CIPSENDL:2048,1024\r\n", 27, b"\r\n\r\n\r\n+CIPSENDL:2048,1024\r\n");
+}
+
 #[test]
 fn test_first_parse_data_available() {
     assert_result(b"+IPD,0,100\r\n", 12, b"+IPD,0,100\r\n");
@@ -125,6 +165,33 @@ fn test_first_parse_data_available() {
     assert_result(b"+IPD,0,100\r\n", 18, b"\r\n\r\n\r\n+IPD,0,100\r\n");
 }
 
+#[test]
+fn test_first_parse_active_data() {
+    assert_result(b"+IPD,0,4:abcd", 13, b"+IPD,0,4:abcd");
+    assert_result(b"+IPD,0,4:ab\r\n", 15, b"\r\n+IPD,0,4:ab\r\n\r\nOK\r\n");
+    assert_result(b"+IPD,12,4:abcd", 14, b"+IPD,12,4:abcdNEXT");
+    assert_result(
+        b"+IPD,0,4,\"2001:db8::1\",443:abcd",
+        31,
+        b"+IPD,0,4,\"2001:db8::1\",443:abcd",
+    );
+}
+
+#[test]
+fn test_first_parse_active_data_incomplete() {
+    assert!(<URCMessages<32> as Parser>::parse(b"+IPD,0,4:abc").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+IPD,0,4,\"10.0.0.1\"").is_err());
+}
+
+#[test]
+fn test_first_parse_data_available_remote() {
+    assert_result(
+        b"+IPD,0,100,\"10.0.0.1\",5000\r\n",
+        28,
+        b"+IPD,0,100,\"10.0.0.1\",5000\r\n",
+    );
+}
+
 #[test]
 fn test_first_parse_data_prefix_incomplete() {
     assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA").is_err());
@@ -139,6 +206,26 @@ fn test_first_parse_data_serial_data_incomplete() {
     assert!(<URCMessages<32> as Parser>::parse(b"\r\n+CIPRECVDATA,5:abcd").is_err());
 }
 
+#[test]
+fn test_first_parse_data_remote_fully_received() {
+    assert_result(
+        b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcde",
+        36,
+        b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcde\r\n\r\nOK\r\n",
+    );
+    assert_result(
+        b"+CIPRECVDATA,5,\"2001:db8::1\",5000:abcde",
+        39,
+        b"+CIPRECVDATA,5,\"2001:db8::1\",5000:abcde\r\n\r\nOK\r\n",
+    );
+}
+
+#[test]
+fn test_first_parse_data_remote_incomplete() {
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA,5,\"2001:db8::1\"").is_err());
+    assert!(<URCMessages<32> as Parser>::parse(b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcd").is_err());
+}
+
 #[test]
 fn test_first_parse_data_fully_received() {
     assert_result(b"+CIPRECVDATA,5:abcde", 20, b"+CIPRECVDATA,5:abcde\r\n\r\nOK\r\n");
@@ -200,9 +287,22 @@ fn test_second_parse_socket_connected_valid_link_id() {
     );
 }
 
+#[test]
+fn test_second_parse_socket_connected_multi_digit_link_id() {
+    assert_eq!(
+        URCMessages::SocketConnected(5),
+        <URCMessages<32> as AtatUrc>::parse(b"5,CONNECT\r\n").unwrap()
+    );
+    assert_eq!(
+        URCMessages::SocketConnected(12),
+        <URCMessages<32> as AtatUrc>::parse(b"12,CONNECT\r\n").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_socket_connected_invalid_link_id() {
-    assert!(<URCMessages<32> as AtatUrc>::parse(b"5,CONNECT\r\n").is_none())
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"x,CONNECT\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b",CONNECT\r\n").is_none());
 }
 
 #[test]
@@ -213,9 +313,30 @@ fn test_second_parse_socket_closed_valid_link_id() {
     );
 }
 
+#[test]
+fn test_second_parse_socket_closed_multi_digit_link_id() {
+    assert_eq!(
+        URCMessages::SocketClosed(15),
+        <URCMessages<32> as AtatUrc>::parse(b"15,CLOSED\r\n").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_socket_closed_invalid_link_id() {
-    assert!(<URCMessages<32> as AtatUrc>::parse(b"5,CLOSED\r\n").is_none())
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"x,CLOSED\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"1x,CLOSED\r\n").is_none());
+}
+
+#[test]
+fn test_second_parse_single_connection() {
+    assert_eq!(
+        URCMessages::SingleConnected,
+        <URCMessages<32> as AtatUrc>::parse(b"CONNECT\r\n").unwrap()
+    );
+    assert_eq!(
+        URCMessages::SingleClosed,
+        <URCMessages<32> as AtatUrc>::parse(b"CLOSED\r\n").unwrap()
+    );
 }
 
 #[test]
@@ -226,6 +347,49 @@ fn test_second_parse_already_connected() {
     );
 }
 
+#[test]
+fn test_second_parse_join_failed() {
+    assert_eq!(
+        URCMessages::JoinFailed(2),
+        <URCMessages<32> as AtatUrc>::parse(b"+CWJAP:2\r\n").unwrap()
+    );
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+CWJAP:x\r\n").is_none());
+}
+
+#[test]
+fn test_second_parse_station_connected() {
+    assert_eq!(
+        URCMessages::StationConnected(String::from("18:fe:34:a2:b1:09")),
+        <URCMessages<32> as AtatUrc>::parse(b"+STA_CONNECTED:\"18:fe:34:a2:b1:09\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_disconnected() {
+    assert_eq!(
+        URCMessages::StationDisconnected(String::from("18:fe:34:a2:b1:09")),
+        <URCMessages<32> as AtatUrc>::parse(b"+STA_DISCONNECTED:\"18:fe:34:a2:b1:09\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_ip_assigned() {
+    assert_eq!(
+        URCMessages::StationIpAssigned(
+            String::from("18:fe:34:a2:b1:09"),
+            IpAddr::from_str("192.168.4.2").unwrap()
+        ),
+        <URCMessages<32> as AtatUrc>::parse(b"+DIST_STA_IP:\"18:fe:34:a2:b1:09\",\"192.168.4.2\"\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_station_event_invalid() {
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+STA_CONNECTED:\"18:fe:34\"\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+STA_UNKNOWN:\"18:fe:34:a2:b1:09\"\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+DIST_STA_IP:\"18:fe:34:a2:b1:09\",\"invalid\"\r\n").is_none());
+}
+
 #[test]
 fn test_second_parse_received_bytes_valid_byte_count() {
     assert_eq!(
@@ -256,6 +420,21 @@ fn test_second_parse_send_fail() {
     );
 }
 
+#[test]
+fn test_second_parse_send_progress_correct() {
+    assert_eq!(
+        URCMessages::SendProgress(2048, 1024),
+        <URCMessages<32> as AtatUrc>::parse(b"+CIPSENDL:2048,1024\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_send_progress_invalid() {
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+CIPSENDL:2048\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+CIPSENDL:2048,1024,5\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+CIPSENDL:a,1024\r\n").is_none());
+}
+
 #[test]
 fn test_second_parse_data_available_correct() {
     assert_eq!(
@@ -264,6 +443,60 @@ fn test_second_parse_data_available_correct() {
     );
 }
 
+#[test]
+fn test_second_parse_active_data() {
+    assert_eq!(
+        URCMessages::ActiveData(3, Vec::<u8, 32>::from_slice(b"abcd").unwrap()),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,4:abcd").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_active_data_remote() {
+    assert_eq!(
+        URCMessages::ActiveRemoteData(
+            1,
+            SocketAddr::from_str("[2001:db8::1]:443").unwrap(),
+            Vec::<u8, 32>::from_slice(b"ab:\r").unwrap()
+        ),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,1,4,\"2001:db8::1\",443:ab:\r").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_active_data_too_large() {
+    assert!(<URCMessages<4> as AtatUrc>::parse(b"+IPD,3,5:abcde").is_none())
+}
+
+#[test]
+fn test_second_parse_data_available_remote_ipv4() {
+    assert_eq!(
+        URCMessages::RemoteDataAvailable(3, 256, SocketAddr::from_str("10.0.0.1:5000").unwrap()),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\",5000\r\n").unwrap()
+    );
+
+    // Unquoted remote IP of older firmware versions
+    assert_eq!(
+        URCMessages::RemoteDataAvailable(3, 256, SocketAddr::from_str("10.0.0.1:5000").unwrap()),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,10.0.0.1,5000\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_data_available_remote_ipv6() {
+    assert_eq!(
+        URCMessages::RemoteDataAvailable(1, 8, SocketAddr::from_str("[2001:db8::1]:443").unwrap()),
+        <URCMessages<32> as AtatUrc>::parse(b"+IPD,1,8,\"2001:db8::1\",443\r\n").unwrap()
+    );
+}
+
+#[test]
+fn test_second_parse_data_available_remote_invalid() {
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\"\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0\",5000\r\n").is_none());
+    assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,256,\"10.0.0.1\",A\r\n").is_none());
+}
+
 #[test]
 fn test_second_parse_data_available_incomplete() {
     assert!(<URCMessages<32> as AtatUrc>::parse(b"+IPD,3,\r\n").is_none());
@@ -289,6 +522,17 @@ fn test_second_parse_data() {
     );
 }
 
+#[test]
+fn test_second_parse_remote_data() {
+    assert_eq!(
+        URCMessages::<32>::RemoteData(
+            SocketAddr::from_str("10.0.0.1:5000").unwrap(),
+            Vec::from_slice(b"abcde").unwrap()
+        ),
+        <URCMessages<32> as AtatUrc>::parse(b"+CIPRECVDATA,5,\"10.0.0.1\",5000:abcde").unwrap()
+    );
+}
+
 #[test]
 fn test_second_parse_longer_then_block_size() {
     // Basically this can just happen if ESP-AT sends more data then requested, which is a protocol violation
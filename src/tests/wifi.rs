@@ -1,8 +1,10 @@
+use crate::stack::{Error as StackError, SocketState};
 use crate::tests::mock::{MockAtatClient, MockTimer};
 use crate::wifi::WifiAdapter;
-use crate::wifi::{Adapter, JoinError};
+use crate::wifi::{Adapter, Encryption, JoinError, LeaveError, ScanError};
 use alloc::string::ToString;
 use atat::Error;
+use embedded_nal::TcpClientStack;
 
 type AdapterType = Adapter<MockAtatClient, MockTimer, 1_000_000, 256, 64>;
 
@@ -52,6 +54,46 @@ fn test_join_connect_command_error() {
     assert_eq!("AT+CWJAP=\"test_wifi\",\"secret\"\r\n".to_string(), commands[1]);
 }
 
+#[test]
+fn test_join_failure_reasons() {
+    let reasons = [
+        (b"+CWJAP:1\r\n", JoinError::Timeout),
+        (b"+CWJAP:2\r\n", JoinError::WrongPassword),
+        (b"+CWJAP:3\r\n", JoinError::AccessPointNotFound),
+        (b"+CWJAP:4\r\n", JoinError::ConnectFailed),
+        (b"+CWJAP:9\r\n", JoinError::ConnectError(Error::Parse)),
+    ];
+
+    for (message, expected) in reasons {
+        let timer = MockTimer::new();
+        let mut client = MockAtatClient::new();
+        client.add_ok_response();
+        client.add_error_response();
+        client.add_urc_message(message);
+
+        let mut adapter: AdapterType = Adapter::new(client, timer);
+        let result = adapter.join("test_wifi", "secret").unwrap_err();
+        assert_eq!(expected, result);
+    }
+}
+
+#[test]
+fn test_join_failure_reason_reset_on_next_attempt() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_urc_message(b"+CWJAP:2\r\n");
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_ok_response();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.join("test_wifi", "secret").unwrap();
+
+    let result = adapter.join("test_wifi", "secret").unwrap_err();
+    assert_eq!(JoinError::ConnectError(Error::Parse), result);
+}
+
 #[test]
 fn test_join_connect_command_would_block() {
     let timer = MockTimer::new();
@@ -207,3 +249,276 @@ fn test_get_join_state_connected_without_ip() {
     assert!(result.connected);
     assert!(!result.ip_assigned);
 }
+
+#[test]
+fn test_start_join_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_join("test_wifi", "secret").unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CWMODE=1\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CWJAP=\"test_wifi\",\"secret\"\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_start_join_invalid_credentials() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let error = adapter.start_join("0123456789abcdef0123456789abcdef0", "secret").unwrap_err();
+    assert_eq!(JoinError::InvalidSSDLength, error);
+    assert!(adapter.client.get_commands_as_strings().is_empty());
+}
+
+#[test]
+fn test_poll_join_connected() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_join("test_wifi", "secret").unwrap();
+    assert_eq!(nb::Error::WouldBlock, adapter.poll_join().unwrap_err());
+
+    adapter.client.add_urc_wifi_connected();
+    assert_eq!(nb::Error::WouldBlock, adapter.poll_join().unwrap_err());
+
+    // Final OK response of CWJAP command is still pending
+    adapter.client.add_urc_wifi_got_ip();
+    assert_eq!(nb::Error::WouldBlock, adapter.poll_join().unwrap_err());
+
+    adapter.client.add_late_ok_response();
+    let state = adapter.poll_join().unwrap();
+    assert!(state.connected);
+    assert!(state.ip_assigned);
+
+    // Join is completed
+    let error = adapter.poll_join().unwrap_err();
+    assert_eq!(nb::Error::Other(JoinError::NoJoinInProgress), error);
+}
+
+#[test]
+fn test_poll_join_discards_previous_state() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_urc_wifi_connected();
+    client.add_urc_wifi_got_ip();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_join("test_wifi", "secret").unwrap();
+    assert_eq!(nb::Error::WouldBlock, adapter.poll_join().unwrap_err());
+}
+
+#[test]
+fn test_poll_join_failure_reason() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_join("test_wifi", "secret").unwrap();
+
+    adapter.client.add_urc_message(b"+CWJAP:2\r\n");
+    adapter.client.add_late_error_response();
+    let error = adapter.poll_join().unwrap_err();
+    assert_eq!(nb::Error::Other(JoinError::WrongPassword), error);
+}
+
+#[test]
+fn test_poll_join_error_response_without_reason() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.start_join("test_wifi", "secret").unwrap();
+
+    adapter.client.add_late_error_response();
+    let error = adapter.poll_join().unwrap_err();
+    assert_eq!(nb::Error::Other(JoinError::ConnectError(Error::Error)), error);
+
+    let error = adapter.poll_join().unwrap_err();
+    assert_eq!(nb::Error::Other(JoinError::NoJoinInProgress), error);
+}
+
+#[test]
+fn test_poll_join_not_started() {
+    let timer = MockTimer::new();
+    let client = MockAtatClient::new();
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+
+    let error = adapter.poll_join().unwrap_err();
+    assert_eq!(nb::Error::Other(JoinError::NoJoinInProgress), error);
+}
+
+#[test]
+fn test_leave_correct_command() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.leave().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(1, commands.len());
+    assert_eq!("AT+CWQAP\r\n".to_string(), commands[0]);
+}
+
+#[test]
+fn test_leave_resets_join_state() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_urc_wifi_connected();
+    client.add_urc_wifi_got_ip();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let state = adapter.get_join_status();
+    assert!(state.connected);
+    assert!(state.ip_assigned);
+
+    adapter.leave().unwrap();
+
+    let state = adapter.get_join_status();
+    assert!(!state.connected);
+    assert!(!state.ip_assigned);
+}
+
+#[test]
+fn test_leave_marks_sockets_as_closing() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let mut socket = TcpClientStack::socket(&mut adapter).unwrap();
+    adapter.sockets[socket.link_id] = SocketState::Connected;
+    adapter.sockets[3] = SocketState::Incoming;
+
+    adapter.leave().unwrap();
+
+    assert_eq!(SocketState::Closing, adapter.sockets[socket.link_id]);
+    assert_eq!(SocketState::Closed, adapter.sockets[3]);
+    assert!(!adapter.is_connected(&socket).unwrap());
+
+    let error = TcpClientStack::send(&mut adapter, &mut socket, b"test").unwrap_err();
+    assert_eq!(nb::Error::Other(StackError::ClosingSocket), error);
+}
+
+#[test]
+fn test_leave_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let error = adapter.leave().unwrap_err();
+
+    assert_eq!(LeaveError::CommandError(Error::Parse), error);
+}
+
+#[test]
+fn test_scan_correct_commands() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    client.add_ok_response();
+    client.add_response(b"+CWLAP:(3,\"test_wifi\",-45,\"10:fe:ed:05:ba:51\",6)\r\n");
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    adapter.scan().unwrap();
+
+    let commands = adapter.client.get_commands_as_strings();
+    assert_eq!(2, commands.len());
+    assert_eq!("AT+CWLAPOPT=1,31\r\n".to_string(), commands[0]);
+    assert_eq!("AT+CWLAP\r\n".to_string(), commands[1]);
+}
+
+#[test]
+fn test_scan_access_points() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    client.add_ok_response();
+    client.add_response(
+        b"+CWLAP:(3,\"test_wifi\",-45,\"10:fe:ed:05:ba:51\",6)\r\n+CWLAP:(0,\"guest\",-80,\"10:fe:ed:05:ba:52\",11)\r\n",
+    );
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    let access_points = adapter.scan().unwrap();
+    assert_eq!(2, access_points.len());
+
+    assert_eq!("test_wifi", access_points[0].ssid.as_str());
+    assert_eq!(-45, access_points[0].rssi);
+    assert_eq!(6, access_points[0].channel);
+    assert_eq!(Encryption::Wpa2Psk, access_points[0].encryption);
+    assert_eq!("10:fe:ed:05:ba:51", access_points[0].bssid.as_str());
+
+    assert_eq!("guest", access_points[1].ssid.as_str());
+    assert_eq!(-80, access_points[1].rssi);
+    assert_eq!(11, access_points[1].channel);
+    assert_eq!(Encryption::Open, access_points[1].encryption);
+    assert_eq!("10:fe:ed:05:ba:52", access_points[1].bssid.as_str());
+}
+
+#[test]
+fn test_scan_no_access_points() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    client.add_ok_response();
+    client.add_ok_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert!(adapter.scan().unwrap().is_empty());
+}
+
+#[test]
+fn test_scan_options_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(ScanError::OptionsError(Error::Parse), adapter.scan().unwrap_err());
+}
+
+#[test]
+fn test_scan_command_error() {
+    let timer = MockTimer::new();
+    let mut client = MockAtatClient::new();
+
+    client.add_ok_response();
+    client.add_error_response();
+
+    let mut adapter: AdapterType = Adapter::new(client, timer);
+    assert_eq!(ScanError::CommandError(Error::Parse), adapter.scan().unwrap_err());
+}
+
+#[test]
+fn test_encryption_from_ecn() {
+    assert_eq!(Encryption::Open, Encryption::from(0));
+    assert_eq!(Encryption::Wep, Encryption::from(1));
+    assert_eq!(Encryption::WpaPsk, Encryption::from(2));
+    assert_eq!(Encryption::Wpa2Psk, Encryption::from(3));
+    assert_eq!(Encryption::WpaWpa2Psk, Encryption::from(4));
+    assert_eq!(Encryption::Wpa2Enterprise, Encryption::from(5));
+    assert_eq!(Encryption::Wpa3Psk, Encryption::from(6));
+    assert_eq!(Encryption::Wpa2Wpa3Psk, Encryption::from(7));
+    assert_eq!(Encryption::WapiPsk, Encryption::from(8));
+    assert_eq!(Encryption::Owe, Encryption::from(9));
+    assert_eq!(Encryption::Unknown(42), Encryption::from(42));
+}
@@ -0,0 +1,310 @@
+//! # UDP stack
+//!
+//! This crate fully implements [UdpClientStack] and [UdpFullStack] of [embedded_nal].
+//!
+//! UDP sockets share the link IDs of ESP-AT with the TCP sockets of [stack module](crate::stack). So the total
+//! number of parallel sockets (TCP + UDP) is limited by the generic constant SOCKETS of [Adapter].
+//!
+//! Each datagram needs to fit into one block, so the max. datagram size is defined by the generic
+//! constants TX_SIZE and RX_SIZE, s. [Adapter] for more details.
+//!
+//! ## Example
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::{SocketAddr, UdpClientStack, UdpFullStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, WifiAdapter};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! // Creating a UDP socket
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.connect(&mut socket, SocketAddr::from_str("10.0.0.1:53").unwrap()).unwrap();
+//!
+//! // Sending a datagram
+//! adapter.send(&mut socket, b"hallo!").unwrap();
+//!
+//! // Receiving a datagram
+//! let mut rx_buffer = [0x0; 64];
+//! let (length, remote) = adapter.receive(&mut socket, &mut rx_buffer).unwrap();
+//! assert_eq!(16, length);
+//! assert_eq!(b"nice to see you!", &rx_buffer[..16]);
+//! assert_eq!("10.0.0.1:53", remote.to_string());
+//!
+//! // Closing socket
+//! adapter.close(socket).unwrap();
+//! ````
+//!
+//! ## Bound socket example
+//!
+//! Bound sockets accept datagrams of any remote. Remote information of received data is enabled
+//! on ESP-AT (CIPDINFO), so that the sender of each datagram is known.
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::{SocketAddr, UdpClientStack, UdpFullStack};
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 1024, 1024> = Adapter::new(client, ExampleTimer::default());
+//!
+//! // Binding a UDP socket to local port
+//! let mut socket = adapter.socket().unwrap();
+//! adapter.bind(&mut socket, 5353).unwrap();
+//!
+//! // Sending a datagram to an arbitrary remote
+//! adapter.send_to(&mut socket, SocketAddr::from_str("10.0.0.2:5353").unwrap(), b"hallo!").unwrap();
+//!
+//! // Receiving a datagram
+//! let mut rx_buffer = [0x0; 64];
+//! let (length, remote) = adapter.receive(&mut socket, &mut rx_buffer).unwrap();
+//! assert_eq!(b"nice to see you!", &rx_buffer[..length]);
+//! assert_eq!("10.0.0.2:5353", remote.to_string());
+//! ````
+use crate::commands::{
+    ConnectCommand, ReceiveDataCommand, RemoteTransmissionPrepareCommand, TransmissionPrepareCommand, UdpBindCommand,
+};
+use crate::stack::{Error, SocketState};
+use crate::wifi::Adapter;
+use atat::AtatClient;
+use atat::Error as AtError;
+use core::cmp::min;
+use embedded_nal::{SocketAddr, UdpClientStack};
+use fugit_timer::Timer;
+
+/// Unique socket for a UDP transmission
+#[derive(Debug)]
+pub struct UdpSocket {
+    /// Unique link id of AT
+    pub(crate) link_id: usize,
+
+    /// Local port if socket was bound by `bind()`
+    pub(crate) local_port: Option<u16>,
+}
+
+impl UdpSocket {
+    pub(crate) fn new(link_id: usize) -> Self {
+        Self {
+            link_id,
+            local_port: None,
+        }
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > UdpClientStack for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    type UdpSocket = UdpSocket;
+    type Error = Error;
+
+    /// Opens and returns a new UDP socket
+    /// Link IDs are shared with TCP sockets. If no socket is available [Error::NoSocketAvailable] is returned.
+    ///
+    /// On first call ESP-AT is configured to support multiple connections.
+    fn socket(&mut self) -> Result<Self::UdpSocket, Self::Error> {
+        self.enable_multiple_connections()?;
+        Ok(UdpSocket::new(self.open_link()?))
+    }
+
+    /// Sets the remote address of the UDP transmission. Both IPv4 and IPv6 are supported.
+    /// Returns [Error::AlreadyConnected] if socket is already connected.
+    ///
+    /// On first call ESP-AT is configured for passive socket receiving mode.
+    fn connect(&mut self, socket: &mut Self::UdpSocket, remote: SocketAddr) -> Result<(), Self::Error> {
+        self.process_urc_messages();
+
+        #[cfg(feature = "active-mode")]
+        self.assert_passive_receiving_mode()?;
+
+        if self.sockets[socket.link_id] == SocketState::Connected {
+            return Err(Error::AlreadyConnected);
+        }
+
+        let command = match remote {
+            SocketAddr::V4(address) => ConnectCommand::udp_v4(socket.link_id, address),
+            SocketAddr::V6(address) => ConnectCommand::udp_v6(socket.link_id, address),
+        };
+
+        self.connect_link(socket.link_id, command, Some(remote))
+    }
+
+    /// Sends the given buffer as single datagram to the connected remote.
+    /// Returns [Error::DatagramTooLarge] if the buffer exceeds the block size defined by TX_SIZE.
+    ///
+    /// Bound sockets have no fixed remote, so `send_to()` needs to be used instead.
+    ///
+    /// Returns [nb::Error::WouldBlock] until the datagram is confirmed by ESP-AT. The next call with
+    /// the same datagram resumes the pending transmission.
+    fn send(&mut self, socket: &mut Self::UdpSocket, buffer: &[u8]) -> nb::Result<(), Self::Error> {
+        self.process_urc_messages();
+
+        if socket.local_port.is_some() {
+            return nb::Result::Err(nb::Error::Other(Error::BoundSocket));
+        }
+
+        self.assert_link_connected(socket.link_id)?;
+        Self::assert_datagram_size(buffer)?;
+
+        if self.poll_pending_send(socket.link_id, buffer.len())?.is_some() {
+            return nb::Result::Ok(());
+        }
+
+        self.send_command(TransmissionPrepareCommand::new(socket.link_id, buffer.len()))?;
+        self.send_chunk(socket.link_id, buffer)?;
+        self.poll_send_confirmation()?;
+
+        nb::Result::Ok(())
+    }
+
+    /// Receives a single datagram (if available) and returns its length and sender.
+    ///
+    /// Just one block of RX_SIZE is read for keeping the datagram boundaries. If the datagram is larger then the
+    /// given buffer, the remaining data of the block is discarded, so each call returns at most one datagram.
+    ///
+    /// RX_SIZE needs to be at least the size of the largest datagram. ESP-AT does not report the boundaries of
+    /// buffered datagrams, so the remainder of a larger datagram is kept and returned by the next call.
+    fn receive(
+        &mut self,
+        socket: &mut Self::UdpSocket,
+        buffer: &mut [u8],
+    ) -> nb::Result<(usize, SocketAddr), Self::Error> {
+        self.process_urc_messages();
+        self.assert_link_ids_supported()?;
+        self.assert_no_pending_send()?;
+
+        if !self.poll_data_available(socket.link_id)? {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        self.data_remote = None;
+
+        let command = ReceiveDataCommand::<RX_SIZE>::new(socket.link_id, RX_SIZE);
+        self.send_command(command)?;
+        self.process_urc_messages();
+
+        if self.data.is_none() {
+            return nb::Result::Err(nb::Error::Other(Error::ReceiveFailed(AtError::InvalidResponse)));
+        }
+
+        let data = self.data.take().unwrap();
+        self.reduce_data_available(socket.link_id, data.len());
+
+        // Remaining data of the block is dropped
+        let length = min(data.len(), buffer.len());
+        buffer[..length].copy_from_slice(&data[..length]);
+
+        // Remote of CIPRECVDATA message takes precedence over the remote of the last +IPD message
+        let remote = match self.data_remote.take().or(self.remotes[socket.link_id]) {
+            None => return nb::Result::Err(nb::Error::Other(Error::ReceiveFailed(AtError::InvalidResponse))),
+            Some(remote) => remote,
+        };
+
+        nb::Result::Ok((length, remote))
+    }
+
+    /// Closes a socket
+    ///
+    /// If the socket is not connected, no command is sent to the ESP-AT but only the internal status is set.
+    /// In case of an error (which is returned) the socket is internally set to closed so that it is not lost and can be reused.
+    fn close(&mut self, socket: Self::UdpSocket) -> Result<(), Self::Error> {
+        self.close_link(socket.link_id)
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > UdpFullStack for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Binds the socket to the given local port. Datagrams of any remote are received.
+    /// Returns [Error::AlreadyConnected] if socket is already connected or bound.
+    ///
+    /// On first call ESP-AT is configured to include the remote information in +IPD messages.
+    fn bind(&mut self, socket: &mut Self::UdpSocket, local_port: u16) -> Result<(), Self::Error> {
+        self.process_urc_messages();
+
+        #[cfg(feature = "active-mode")]
+        self.assert_passive_receiving_mode()?;
+
+        if self.sockets[socket.link_id] == SocketState::Connected {
+            return Err(Error::AlreadyConnected);
+        }
+
+        self.enable_data_info()?;
+        self.connect_link(socket.link_id, UdpBindCommand::new(socket.link_id, local_port), None)?;
+
+        socket.local_port = Some(local_port);
+        Ok(())
+    }
+
+    /// Sends the given buffer as single datagram to the given remote.
+    /// Returns [Error::UnboundSocket] if socket was not bound by `bind()` before.
+    /// Returns [Error::DatagramTooLarge] if the buffer exceeds the block size defined by TX_SIZE.
+    ///
+    /// Returns [nb::Error::WouldBlock] until the datagram is confirmed by ESP-AT. The next call with
+    /// the same datagram resumes the pending transmission.
+    fn send_to(
+        &mut self,
+        socket: &mut Self::UdpSocket,
+        remote: SocketAddr,
+        buffer: &[u8],
+    ) -> nb::Result<(), Self::Error> {
+        self.process_urc_messages();
+
+        if socket.local_port.is_none() {
+            return nb::Result::Err(nb::Error::Other(Error::UnboundSocket));
+        }
+
+        self.assert_link_connected(socket.link_id)?;
+        self.assert_remote_supported(&remote)?;
+        Self::assert_datagram_size(buffer)?;
+
+        if self.poll_pending_send(socket.link_id, buffer.len())?.is_some() {
+            return nb::Result::Ok(());
+        }
+
+        self.send_command(RemoteTransmissionPrepareCommand::new(
+            socket.link_id,
+            buffer.len(),
+            remote,
+        ))?;
+        self.send_chunk(socket.link_id, buffer)?;
+        self.poll_send_confirmation()?;
+
+        nb::Result::Ok(())
+    }
+}
+
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
+{
+    /// Asserts that the given datagram fits into a single TX block
+    fn assert_datagram_size(buffer: &[u8]) -> Result<(), Error> {
+        if buffer.len() > TX_SIZE {
+            return Err(Error::DatagramTooLarge);
+        }
+
+        Ok(())
+    }
+}
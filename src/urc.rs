@@ -4,7 +4,9 @@
 //! [AtDigester](atat::digest::AtDigester): `AtDigester<URCMessages>`.
 use atat::digest::ParseError;
 use atat::{AtatUrc, Parser};
-use heapless::Vec;
+use core::str::FromStr;
+use embedded_nal::{IpAddr, SocketAddr};
+use heapless::{String, Vec};
 
 /// URC definitions, needs to passed as generic of [AtDigester](atat::digest::AtDigester): `AtDigester<URCMessages>`
 #[derive(Debug, PartialEq, Eq)]
@@ -17,14 +19,23 @@ pub enum URCMessages<const RX_SIZE: usize> {
     WifiDisconnected,
     /// Received an IP from the access point
     ReceivedIP,
+    /// Joining the access point failed with the given error code
+    JoinFailed(usize),
     /// Socket with the given link_id connected
     SocketConnected(usize),
     /// Socket with the given link_id closed
     SocketClosed(usize),
+    /// Connection of the single connection mode (CIPMUX=0) established
+    SingleConnected,
+    /// Connection of the single connection mode (CIPMUX=0) closed
+    SingleClosed,
     /// Confirmation that the given number of bytes have been received by ESP-AT
     ReceivedBytes(usize),
     /// Signals that socket is already connected when trying to establish the same connection again
     AlreadyConnected,
+    /// Progress of a long transmission (CIPSENDL).
+    /// First value = byte count sent to ESP-AT, Second value = byte count sent to the remote
+    SendProgress(usize, usize),
     /// Transmission of socket data was successful
     SendConfirmation,
     /// Transmission of socket data failed
@@ -32,8 +43,23 @@ pub enum URCMessages<const RX_SIZE: usize> {
     /// Data is available in passive receiving mode.
     /// First value = link_id, Second value = available byte count
     DataAvailable(usize, usize),
+    /// Data is available in passive receiving mode, including the remote address (AT+CIPDINFO=1).
+    /// First value = link_id, Second value = available byte count, Third value = remote address
+    RemoteDataAvailable(usize, usize, SocketAddr),
+    /// Received data of the given link_id in active receiving mode
+    ActiveData(usize, Vec<u8, RX_SIZE>),
+    /// Received data of the given link_id in active receiving mode, including the remote address (AT+CIPDINFO=1).
+    ActiveRemoteData(usize, SocketAddr, Vec<u8, RX_SIZE>),
     /// Received the following data requested by CIPRECVDATA command.
     Data(Vec<u8, RX_SIZE>),
+    /// Received the following data requested by CIPRECVDATA command, including the remote address (AT+CIPDINFO=1).
+    RemoteData(SocketAddr, Vec<u8, RX_SIZE>),
+    /// Station with the given MAC address connected to the SoftAP
+    StationConnected(String<17>),
+    /// Station with the given MAC address disconnected from the SoftAP
+    StationDisconnected(String<17>),
+    /// SoftAP assigned an IP to a station. First value = MAC address, Second value = IP address
+    StationIpAssigned(String<17>, IpAddr),
     /// Echo of a command
     Echo,
     /// Unknown URC message
@@ -50,18 +76,43 @@ impl<const RX_SIZE: usize> AtatUrc for URCMessages<RX_SIZE> {
         }
 
         if &resp[..4] == b"+IPD" {
+            if let Ok(message) = DataResponseParser::active(resp).parse() {
+                let link_id = message.link_id?;
+                return match message.remote {
+                    None => Some(Self::ActiveData(link_id, message.to_vec()?)),
+                    Some(remote) => Some(Self::ActiveRemoteData(link_id, remote, message.to_vec()?)),
+                };
+            }
+
             return URCMessages::parse_data_available(resp);
         }
 
         if resp.len() > 15 && &resp[..13] == b"+CIPRECVDATA," {
             let message = DataResponseParser::new(resp).parse().ok()?;
-            return Some(Self::Data(message.to_vec()?));
+            return match message.remote {
+                None => Some(Self::Data(message.to_vec()?)),
+                Some(remote) => Some(Self::RemoteData(remote, message.to_vec()?)),
+            };
+        }
+
+        if resp.starts_with(b"+CWJAP:") {
+            return Some(Self::JoinFailed(URCMessages::<8>::parse_join_error_code(resp)?));
+        }
+
+        if resp.starts_with(b"+CIPSENDL:") {
+            return URCMessages::parse_send_progress(resp);
         }
 
-        match &resp[1..resp.len() - 2] {
-            b",CONNECT" => return Some(Self::SocketConnected(URCMessages::<8>::parse_link_id(resp[0])?)),
-            b",CLOSED" => return Some(Self::SocketClosed(URCMessages::<8>::parse_link_id(resp[0])?)),
-            _ => {}
+        if resp.starts_with(b"+STA_") || resp.starts_with(b"+DIST_STA_IP:") {
+            return URCMessages::parse_station_event(resp);
+        }
+
+        let line = &resp[..resp.len() - 2];
+        if let Some(link_id) = line.strip_suffix(b",CONNECT") {
+            return Some(Self::SocketConnected(URCMessages::<8>::parse_link_id(link_id)?));
+        }
+        if let Some(link_id) = line.strip_suffix(b",CLOSED") {
+            return Some(Self::SocketClosed(URCMessages::<8>::parse_link_id(link_id)?));
         }
 
         if &resp[..4] == b"Recv" {
@@ -76,24 +127,55 @@ impl<const RX_SIZE: usize> AtatUrc for URCMessages<RX_SIZE> {
             b"WIFI DISCONNECT" => Some(Self::WifiDisconnected),
             b"WIFI GOT IP" => Some(Self::ReceivedIP),
             b"ALREADY CONNECTED" => Some(Self::AlreadyConnected),
+            b"CONNECT" => Some(Self::SingleConnected),
+            b"CLOSED" => Some(Self::SingleClosed),
             _ => Some(Self::Unknown),
         }
     }
 }
 
 impl<const RX_SIZE: usize> URCMessages<RX_SIZE> {
-    /// Parses the socket id. Currently supports just socket 0-4
-    fn parse_link_id(link_id: u8) -> Option<usize> {
-        match link_id {
-            0x30 => Some(0),
-            0x31 => Some(1),
-            0x32 => Some(2),
-            0x33 => Some(3),
-            0x34 => Some(4),
+    /// Returns the link ID referenced by socket related messages
+    pub(crate) fn link_id(&self) -> Option<usize> {
+        match self {
+            Self::SocketConnected(link_id)
+            | Self::SocketClosed(link_id)
+            | Self::DataAvailable(link_id, _)
+            | Self::RemoteDataAvailable(link_id, _, _)
+            | Self::ActiveData(link_id, _)
+            | Self::ActiveRemoteData(link_id, _, _) => Some(*link_id),
             _ => None,
         }
     }
 
+    /// Parses the decimal socket id, e.g. '0' or '12'
+    fn parse_link_id(link_id: &[u8]) -> Option<usize> {
+        if link_id.is_empty() || !link_id.iter().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        core::str::from_utf8(link_id).ok()?.parse().ok()
+    }
+
+    /// Parses the progress message of long transmissions, e.g. '+CIPSENDL:2048,1024'
+    fn parse_send_progress(resp: &[u8]) -> Option<Self> {
+        let string = core::str::from_utf8(&resp[10..resp.len() - 2]).ok()?;
+        let mut parts = string.split(',');
+
+        let sent = parts.next()?.parse().ok()?;
+        let confirmed = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self::SendProgress(sent, confirmed))
+    }
+
+    /// Parses the error code of '+CWJAP:N'
+    fn parse_join_error_code(resp: &[u8]) -> Option<usize> {
+        core::str::from_utf8(&resp[7..resp.len() - 2]).ok()?.parse().ok()
+    }
+
     /// Tries to parse the N byte count of 'Recv N bytes'
     fn parse_receive_byte_count(resp: &[u8]) -> Option<usize> {
         let postfix_start = resp.len() - 8;
@@ -108,18 +190,69 @@ impl<const RX_SIZE: usize> URCMessages<RX_SIZE> {
         None
     }
 
-    /// Parses the +IPD message
+    /// Parses the +IPD message. Remote IP and port are optional, depending on AT+CIPDINFO.
     fn parse_data_available(data: &[u8]) -> Option<Self> {
         let string = core::str::from_utf8(&data[..data.len() - 2]).ok()?;
         let mut parts = string.split(',');
 
         let link_id = parts.nth(1)?.parse().ok()?;
-        let length = parts.last()?.parse().ok()?;
+        let length = parts.next()?.parse().ok()?;
+
+        match parts.next() {
+            None => Some(Self::DataAvailable(link_id, length)),
+            Some(ip) => {
+                let remote = parse_remote(ip, parts.next()?)?;
+                if parts.next().is_some() {
+                    return None;
+                }
+
+                Some(Self::RemoteDataAvailable(link_id, length, remote))
+            }
+        }
+    }
 
-        Some(Self::DataAvailable(link_id, length))
+    /// Parses the SoftAP station messages +STA_CONNECTED, +STA_DISCONNECTED and +DIST_STA_IP
+    fn parse_station_event(data: &[u8]) -> Option<Self> {
+        let string = core::str::from_utf8(&data[..data.len() - 2]).ok()?;
+        let (prefix, arguments) = string.split_once(':')?;
+
+        // MAC addresses contain colons, so arguments are split by comma
+        let mut parts = arguments.split(',');
+        let mac = parse_mac(parts.next()?)?;
+
+        let message = match prefix {
+            "+STA_CONNECTED" => Self::StationConnected(mac),
+            "+STA_DISCONNECTED" => Self::StationDisconnected(mac),
+            "+DIST_STA_IP" => Self::StationIpAssigned(mac, IpAddr::from_str(parts.next()?.trim_matches('"')).ok()?),
+            _ => return None,
+        };
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(message)
     }
 }
 
+/// Parses a optionally quoted MAC address
+fn parse_mac(mac: &str) -> Option<String<17>> {
+    let mac = mac.trim_matches('"');
+    if mac.len() != 17 {
+        return None;
+    }
+
+    Some(String::from(mac))
+}
+
+/// Parses a optionally quoted remote IP and the remote port
+fn parse_remote(ip: &str, port: &str) -> Option<SocketAddr> {
+    let ip = IpAddr::from_str(ip.trim_matches('"')).ok()?;
+    let port = port.parse().ok()?;
+
+    Some(SocketAddr::new(ip, port))
+}
+
 impl<const RX_SIZE: usize> Parser for URCMessages<RX_SIZE> {
     fn parse(buf: &[u8]) -> Result<(&[u8], usize), ParseError> {
         if buf.len() < 6 {
@@ -134,38 +267,58 @@ impl<const RX_SIZE: usize> Parser for URCMessages<RX_SIZE> {
     }
 }
 
-/// Matches length defined URC message +CIPRECVDATA
+/// Matches length defined URC messages +CIPRECVDATA and +IPD of active receiving mode
 struct SizeBasedMatcher<'a> {
     buffer: &'a [u8],
 
     /// First index where the actual message starts
     start: usize,
+
+    /// True if a +IPD message of active receiving mode was matched
+    active: bool,
 }
 
 impl<'a> SizeBasedMatcher<'a> {
     /// Returns Self if buffer contains a sized encoded message
     pub fn matches(buffer: &'a [u8]) -> Option<Self> {
-        if buffer.len() < 15 {
-            return None;
-        }
-
         let start = buffer.iter().enumerate().find(|x| x.1 != &b'\r' && x.1 != &b'\n')?.0;
-
         let data = &buffer[start..];
-        if data.len() < 13 || &data[..13] != b"+CIPRECVDATA," {
-            return None;
+
+        if buffer.len() >= 15 && data.len() >= 13 && &data[..13] == b"+CIPRECVDATA," {
+            return Some(Self {
+                buffer,
+                start,
+                active: false,
+            });
+        }
+
+        // +IPD messages of passive mode are terminated by CRLF and matched line based
+        if data.starts_with(b"+IPD,")
+            && !matches!(
+                DataResponseParser::active(data).find_separator(),
+                Err(ParseError::NoMatch)
+            )
+        {
+            return Some(Self {
+                buffer,
+                start,
+                active: true,
+            });
         }
 
-        Some(Self { buffer, start })
+        None
     }
 
     /// Parses the message and checks if data is complete
     pub fn handle(self) -> Result<(&'a [u8], usize), ParseError> {
         let data = &self.buffer[self.start..];
-        let message = DataResponseParser::new(data).parse()?;
+        let message = match self.active {
+            true => DataResponseParser::active(data).parse()?,
+            false => DataResponseParser::new(data).parse()?,
+        };
 
-        let total_length = self.start + 13 + message.length_str.len() + 1 + message.length;
-        Ok((&data[..total_length - self.start], total_length))
+        let length = message.prefix_length + message.header.len() + 1 + message.length;
+        Ok((&data[..length], self.start + length))
     }
 }
 
@@ -220,13 +373,36 @@ impl<'a> LineBasedMatcher<'a> {
             || &line[..4] == "+IPD"
             || line == "SEND OK"
             || line == "SEND FAIL"
+            || line.starts_with("+CIPSENDL:")
             || &line[..4] == "WIFI"
-            || &line[1..] == ",CONNECT"
-            || &line[1..] == ",CLOSED"
+            || self.matches_link_message(line, ",CONNECT")
+            || self.matches_link_message(line, ",CLOSED")
             || line == "ALREADY CONNECTED"
+            || line == "CONNECT"
+            || line == "CLOSED"
+            || self.matches_join_error(line)
+            || line.starts_with("+STA_CONNECTED:")
+            || line.starts_with("+STA_DISCONNECTED:")
+            || line.starts_with("+DIST_STA_IP:")
             || self.matches_receive_confirmation(line)
     }
 
+    /// Returns true if line is matching a socket message with the given suffix, e.g. "12,CONNECT"
+    fn matches_link_message(&self, line: &str, suffix: &str) -> bool {
+        match line.strip_suffix(suffix) {
+            None => false,
+            Some(link_id) => !link_id.is_empty() && link_id.bytes().all(|byte| byte.is_ascii_digit()),
+        }
+    }
+
+    /// Returns true if line is matching a join error code e.g. "+CWJAP:2". Query responses are not matched.
+    fn matches_join_error(&self, line: &str) -> bool {
+        match line.strip_prefix("+CWJAP:") {
+            None => false,
+            Some(code) => !code.is_empty() && code.bytes().all(|byte| byte.is_ascii_digit()),
+        }
+    }
+
     /// Returns true if line is matching a receive confirmation e.g. "Recv 9 bytes"
     fn matches_receive_confirmation(&self, line: &str) -> bool {
         if line.len() < 12 {
@@ -242,27 +418,63 @@ impl<'a> LineBasedMatcher<'a> {
     }
 }
 
-/// Decodes a +CIPRECVDATA message
+/// Decodes a +CIPRECVDATA message or a +IPD message of active receiving mode
 struct DataResponseParser<'a> {
     buffer: &'a [u8],
+
+    /// Length of the message prefix, e.g. `+CIPRECVDATA,`
+    prefix_length: usize,
+
+    /// True if the header starts with the link ID
+    with_link_id: bool,
 }
 
 impl<'a> DataResponseParser<'a> {
     pub fn new(buffer: &'a [u8]) -> Self {
-        Self { buffer }
+        Self {
+            buffer,
+            prefix_length: 13,
+            with_link_id: false,
+        }
+    }
+
+    /// Decoder for +IPD messages of active receiving mode, e.g. `+IPD,0,4:data`
+    pub fn active(buffer: &'a [u8]) -> Self {
+        Self {
+            buffer,
+            prefix_length: 5,
+            with_link_id: true,
+        }
     }
 
-    /// Parses the length and returns both the usize length + length string
+    /// Parses the header (optional link ID + length + optional remote address) and returns the decoded message
     pub fn parse(self) -> Result<DataMessage<'a>, ParseError> {
-        let separator = self
-            .buffer
-            .iter()
-            .enumerate()
-            .find(|x| x.1 == &b':')
-            .ok_or(ParseError::Incomplete)?
-            .0;
-        let length_str = core::str::from_utf8(&self.buffer[13..separator]).map_err(|_| ParseError::NoMatch)?;
-        let length_usize = length_str.parse::<usize>().map_err(|_| ParseError::NoMatch)?;
+        let separator = self.find_separator()?;
+        let header =
+            core::str::from_utf8(&self.buffer[self.prefix_length..separator]).map_err(|_| ParseError::NoMatch)?;
+
+        let mut parts = header.split(',');
+        let link_id = match self.with_link_id {
+            false => None,
+            true => Some(
+                parts
+                    .next()
+                    .ok_or(ParseError::NoMatch)?
+                    .parse::<usize>()
+                    .map_err(|_| ParseError::NoMatch)?,
+            ),
+        };
+
+        let length_usize = parts
+            .next()
+            .ok_or(ParseError::NoMatch)?
+            .parse::<usize>()
+            .map_err(|_| ParseError::NoMatch)?;
+
+        let remote = match parts.next() {
+            None => None,
+            Some(ip) => Some(parse_remote(ip, parts.next().ok_or(ParseError::NoMatch)?).ok_or(ParseError::NoMatch)?),
+        };
 
         let remaining_data = &self.buffer[separator + 1..];
         if remaining_data.len() < length_usize {
@@ -270,20 +482,49 @@ impl<'a> DataResponseParser<'a> {
         }
 
         Ok(DataMessage {
+            link_id,
+            prefix_length: self.prefix_length,
             length: length_usize,
-            length_str,
+            header,
+            remote,
             data: remaining_data,
         })
     }
+
+    /// Returns the index of the first colon, which is not part of a quoted IPv6 address.
+    /// Returns [ParseError::NoMatch] if the header is terminated by CRLF instead.
+    fn find_separator(&self) -> Result<usize, ParseError> {
+        let mut quoted = false;
+
+        for (index, byte) in self.buffer.iter().enumerate().skip(self.prefix_length) {
+            match byte {
+                b'"' => quoted = !quoted,
+                b':' if !quoted => return Ok(index),
+                b'\r' if !quoted => return Err(ParseError::NoMatch),
+                _ => {}
+            }
+        }
+
+        Err(ParseError::Incomplete)
+    }
 }
 
 /// Decoded data message
 struct DataMessage<'a> {
+    /// Link ID, just included in +IPD messages
+    pub link_id: Option<usize>,
+
+    /// Length of the message prefix
+    pub prefix_length: usize,
+
     /// Serial data length
     pub length: usize,
 
-    /// Serial data length as string
-    pub length_str: &'a str,
+    /// Header between prefix and separator, e.g. `5` or `5,"10.0.0.1",5000`
+    pub header: &'a str,
+
+    /// Remote address, if included in header
+    pub remote: Option<SocketAddr>,
 
     /// All data after separator
     pub data: &'a [u8],
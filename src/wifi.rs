@@ -1,10 +1,10 @@
 //! # WIFI access point client
 //!
-//! Joining a network and obtaining address information is supported.
+//! Joining a network, obtaining address information and static IP configuration is supported.
 //!
 //! Note: If the connection was not successful or is lost, the ESP-AT will try independently fro time
 //! to time (by default every second) to establish connection to the network. The status can be
-//! queried using `get_join_state()`.
+//! queried using `get_join_state()`. Reconnecting is stopped by leaving the network using `leave()`.
 //!
 //! ## Example
 //!
@@ -28,21 +28,93 @@
 //! assert_eq!("10:fe:ed:05:ba:50", address.mac.unwrap().as_str());
 //! assert_eq!("10.0.0.181", address.ipv4.unwrap().to_string());
 //! ````
+//!
+//! ## Non-blocking join
+//!
+//! `join()` blocks until ESP-AT confirms the connection, which may take several seconds. Alternatively
+//! the join is started by `start_join()` and polled by `poll_join()`, which completes as soon as an
+//! IP has been assigned.
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::Adapter;
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 256, 256> = Adapter::new(client, ExampleTimer::default());
+//!
+//! adapter.start_join("test_wifi", "secret").unwrap();
+//!
+//! let state = nb::block!(adapter.poll_join()).unwrap();
+//! assert!(state.connected);
+//! assert!(state.ip_assigned);
+//! ````
+//!
+//! ## Scanning
+//!
+//! Nearby access points are listed by `scan()`, sorted by signal strength. Station mode needs to be
+//! enabled, which is the default mode of ESP-AT.
+//!
+//! ````
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, Encryption};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 256, 256> = Adapter::new(client, ExampleTimer::default());
+//!
+//! let access_points = adapter.scan().unwrap();
+//! assert_eq!(2, access_points.len());
+//! assert_eq!("test_wifi", access_points[0].ssid.as_str());
+//! assert_eq!(-45, access_points[0].rssi);
+//! assert_eq!(Encryption::Wpa2Psk, access_points[0].encryption);
+//! ````
+//!
+//! ## Static IP
+//!
+//! By default the IPv4 address is assigned by DHCP. Setting a static address by `set_static_ip()`
+//! disables DHCP, which may be enabled again by `set_dhcp()`.
+//!
+//! ````
+//! # use core::str::FromStr;
+//! # use embedded_nal::Ipv4Addr;
+//! # use esp_at_nal::example::ExampleTimer;
+//! # use esp_at_nal::wifi::{Adapter, IpConfig, WifiAdapter};
+//! # use crate::esp_at_nal::example::ExampleAtClient as AtClient;
+//! #
+//! let client = AtClient::default();
+//! let mut adapter: Adapter<_, _, 1_000_000, 256, 256> = Adapter::new(client, ExampleTimer::default());
+//!
+//! adapter.set_static_ip(&IpConfig {
+//!     ip: Ipv4Addr::from_str("10.0.0.181").unwrap(),
+//!     gateway: Ipv4Addr::from_str("10.0.0.1").unwrap(),
+//!     netmask: Ipv4Addr::from_str("255.255.255.0").unwrap(),
+//! }).unwrap();
+//!
+//! adapter.join("test_wifi", "secret").unwrap();
+//!
+//! let config = adapter.get_ip_config().unwrap();
+//! assert_eq!("10.0.0.1", config.gateway.to_string());
+//! assert_eq!("255.255.255.0", config.netmask.to_string());
+//! ````
 use crate::commands::{
-    AccessPointConnectCommand, CommandErrorHandler, ObtainLocalAddressCommand, SetSocketReceivingModeCommand,
-    WifiModeCommand,
+    AccessPointConnectCommand, AccessPointDisconnectCommand, AccessPointListOptionsCommand, CommandErrorHandler,
+    IpConfigQueryCommand, ListAccessPointsCommand, ObtainLocalAddressCommand, SetDhcpCommand,
+    SetSocketReceivingModeCommand, SetStaticIpCommand, UnconfirmedCommand, WifiModeCommand,
 };
-use crate::responses::LocalAddressResponse;
-use crate::stack::SocketState;
+use crate::firmware::Capabilities;
+use crate::responses::{AccessPointResponse, LocalAddressResponse};
+use crate::soft_ap::StationEvent;
+use crate::stack::{PendingSend, SocketOptions, SocketState};
 use crate::urc::URCMessages;
 use atat::heapless::Vec;
-use atat::{AtatClient, AtatCmd, Error as AtError};
+use atat::{AtatClient, AtatCmd, AtatResp, Error as AtError};
 use core::fmt::Debug;
 use core::str::FromStr;
-use embedded_nal::{Ipv4Addr, Ipv6Addr};
-use fugit::{ExtU32, TimerDurationU32};
+use embedded_nal::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use fugit::{ExtU32, TimerDurationU32, TimerInstantU32};
 use fugit_timer::Timer;
-use heapless::String;
+use heapless::{Deque, String};
 
 /// Wifi network adapter trait
 pub trait WifiAdapter {
@@ -68,7 +140,17 @@ pub trait WifiAdapter {
 /// introduces also higher stack memory footprint. Max. value: 8192
 ///
 /// RX_SIZE: Chunk size in bytes when receiving data. Value should be matched to buffer size of `receive()` calls.
-pub struct Adapter<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usize, const RX_SIZE: usize> {
+///
+/// SOCKETS: Max. number of parallel sockets (TCP + UDP). Needs to match `CONFIG_AT_SOCKET_MAX_CONN_NUM` of the
+/// ESP-AT firmware. Default: 5, max. value: 16
+pub struct Adapter<
+    A: AtatClient,
+    T: Timer<TIMER_HZ>,
+    const TIMER_HZ: u32,
+    const TX_SIZE: usize,
+    const RX_SIZE: usize,
+    const SOCKETS: usize = 5,
+> {
     /// ATAT client
     pub(crate) client: A,
 
@@ -79,22 +161,102 @@ pub struct Adapter<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const
     pub(crate) send_timeout: TimerDurationU32<TIMER_HZ>,
 
     /// Currently joined to WIFI network? Gets updated by URC messages.
-    joined: bool,
+    pub(crate) joined: bool,
+
+    /// Error code of the last failed join attempt. Gets updated by URC message.
+    join_error_code: Option<usize>,
+
+    /// True if a join was started by `start_join()` and is not completed yet
+    pub(crate) join_pending: bool,
+
+    /// Final response of the CWJAP command sent by `start_join()`. None if not received yet.
+    pub(crate) join_response: Option<Result<(), AtError>>,
 
     /// True if an IP was assigned by access point. Get updated by URC message.
-    ip_assigned: bool,
+    pub(crate) ip_assigned: bool,
+
+    /// True if ESP-AT signaled a restart, which was not returned by `modem_restarted()` yet
+    pub(crate) restarted: bool,
+
+    /// True if station mode is used, i.e. `join()` was called
+    pub(crate) station_enabled: bool,
+
+    /// True if the SoftAP was started
+    pub(crate) soft_ap_enabled: bool,
+
+    /// Queued station events of the SoftAP. Gets updated by URC messages.
+    pub(crate) station_events: Deque<StationEvent, 8>,
 
     /// True if multiple connections have been enabled
     pub(crate) multi_connections_enabled: bool,
 
-    /// True if socket passive receiving mode is enabled
-    pub(crate) passive_mode_enabled: bool,
+    /// True if the passthrough (transparent transmission) mode is active, so any sent data is forwarded
+    pub(crate) passthrough_active: bool,
+
+    /// True if the connection of the single connection mode is established. Gets updated by URC messages.
+    pub(crate) single_connected: bool,
+
+    /// True if socket receiving mode (passive or active) is configured
+    pub(crate) receiving_mode_configured: bool,
+
+    /// True if socket data is received in active mode and buffered internally
+    #[cfg(feature = "active-mode")]
+    pub(crate) active_mode: bool,
+
+    /// Buffered socket data of active receiving mode. Array index = link_id
+    #[cfg(feature = "active-mode")]
+    pub(crate) buffers: [Deque<u8, RX_SIZE>; SOCKETS],
+
+    /// True if buffered data exceeded the buffer capacity in active receiving mode. Array index = link_id
+    #[cfg(feature = "active-mode")]
+    pub(crate) buffer_overflow: [bool; SOCKETS],
+
+    /// True if remote information of received data is enabled
+    pub(crate) data_info_enabled: bool,
+
+    /// Local port of the running TCP server, None if no server is running
+    pub(crate) server_port: Option<u16>,
+
+    /// Max. number of clients allowed to connect to the TCP server
+    pub(crate) server_max_connections: usize,
 
     /// Current socket states, array index = link_id
-    pub(crate) sockets: [SocketState; 5],
+    pub(crate) sockets: [SocketState; SOCKETS],
+
+    /// True if a connect command of the link was sent, but not confirmed by a connect message yet.
+    /// Array index = link_id
+    pub(crate) connecting: [bool; SOCKETS],
 
     /// Data length available to receive which is buffered by ESP-AT. Array index = link_id
-    pub(crate) data_available: [usize; 5],
+    pub(crate) data_available: [usize; SOCKETS],
+
+    /// Point in time the receive lengths get polled, if no data is received until then. Array index = link_id
+    pub(crate) receive_poll_deadlines: [Option<TimerInstantU32<TIMER_HZ>>; SOCKETS],
+
+    /// Idle time of receiving after which the receive lengths are polled, None if polling is disabled
+    pub(crate) receive_length_polling: Option<TimerDurationU32<TIMER_HZ>>,
+
+    /// Last link ID referenced by ESP-AT, which exceeds the configured socket count
+    pub(crate) unsupported_link_id: Option<usize>,
+
+    /// Remote address of each link. Gets set on connect and updated by +IPD messages including remote information.
+    /// Array index = link_id
+    pub(crate) remotes: [Option<SocketAddr>; SOCKETS],
+
+    /// TCP options applied on connect, None if no options were set. Array index = link_id
+    pub(crate) socket_options: [Option<SocketOptions>; SOCKETS],
+
+    /// Features supported by the firmware
+    pub(crate) capabilities: Capabilities,
+
+    /// True if TCP data is sent by CIPSENDL command
+    pub(crate) long_transmission: bool,
+
+    /// Byte count sent to ESP-AT reported by the last progress message of a long transmission
+    pub(crate) send_progress: Option<usize>,
+
+    /// Transmission waiting for confirmation. Gets resumed by the next 'send()' call of the same link
+    pub(crate) pending_send: Option<PendingSend<TIMER_HZ>>,
 
     /// Received byte count confirmed by URC message. Gets reset to NONE by 'send()' method
     pub(crate) recv_byte_count: Option<usize>,
@@ -109,6 +271,9 @@ pub struct Adapter<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const
 
     /// Received socket data by URC message
     pub(crate) data: Option<Vec<u8, RX_SIZE>>,
+
+    /// Remote address of received socket data, if included in URC message
+    pub(crate) data_remote: Option<SocketAddr>,
 }
 
 /// Possible errors when joining an access point
@@ -123,17 +288,52 @@ pub enum JoinError {
     /// Error while setting WIFI credentials
     ConnectError(AtError),
 
+    /// Connecting to the access point timed out (+CWJAP error code 1)
+    Timeout,
+
+    /// Password is incorrect (+CWJAP error code 2)
+    WrongPassword,
+
+    /// Access point with the given SSID was not found (+CWJAP error code 3)
+    AccessPointNotFound,
+
+    /// Connection to the access point failed (+CWJAP error code 4)
+    ConnectFailed,
+
     /// Given SSD is longer then the max. size of 32 chars
     InvalidSSDLength,
 
     /// Given password is longer then the max. size of 63 chars
     InvalidPasswordLength,
 
+    /// `poll_join()` was called without starting a join by `start_join()`
+    NoJoinInProgress,
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
     /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
     /// This must be either timeout or blocking.
     UnexpectedWouldBlock,
 }
 
+impl JoinError {
+    /// Maps the error code of +CWJAP message, falls back to the given command error for unknown codes
+    pub(crate) fn from_code(code: usize, error: AtError) -> Self {
+        match code {
+            1 => JoinError::Timeout,
+            2 => JoinError::WrongPassword,
+            3 => JoinError::AccessPointNotFound,
+            4 => JoinError::ConnectFailed,
+            _ => JoinError::ConnectError(error),
+        }
+    }
+}
+
 /// Errors when receiving local address information
 #[derive(Clone, Debug, PartialEq)]
 pub enum AddressErrors {
@@ -143,6 +343,73 @@ pub enum AddressErrors {
     /// Error while parsing addresses
     AddressParseError,
 
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+/// Errors of the IP configuration of the station interface
+#[derive(Clone, Debug, PartialEq)]
+pub enum IpConfigError {
+    /// Error while setting or querying the IP configuration (CIPSTA command)
+    ConfigurationError(AtError),
+
+    /// Error while enabling or disabling DHCP (CWDHCP command)
+    DhcpError(AtError),
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+/// Errors when leaving a WIFI network
+#[derive(Clone, Debug, PartialEq)]
+pub enum LeaveError {
+    /// CWQAP command failed
+    CommandError(AtError),
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
+    /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
+    /// This must be either timeout or blocking.
+    UnexpectedWouldBlock,
+}
+
+/// Errors when scanning for access points
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScanError {
+    /// CWLAPOPT command failed
+    OptionsError(AtError),
+
+    /// CWLAP command failed
+    CommandError(AtError),
+
+    /// Passthrough mode is active, so ESP-AT does not process any commands. Needs to be closed by
+    /// `close_passthrough()` of [Adapter] first.
+    PassthroughActive,
+
+    /// A socket transmission is still unconfirmed. Needs to be completed by calling `send()` again first.
+    SendPending,
+
     /// Received an unexpected WouldBlock. The most common cause of errors is an incorrect mode of the client.
     /// This must be either timeout or blocking.
     UnexpectedWouldBlock,
@@ -158,8 +425,14 @@ pub struct JoinState {
     pub ip_assigned: bool,
 }
 
-impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usize, const RX_SIZE: usize> WifiAdapter
-    for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE>
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > WifiAdapter for Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
 {
     type JoinError = JoinError;
     type AddressError = AddressErrors;
@@ -171,6 +444,7 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
     /// to time (by default every second) to establish connection to the network. The status can be
     /// queried using `get_join_state()`.
     fn join(&mut self, ssid: &str, key: &str) -> Result<JoinState, JoinError> {
+        self.join_pending = false;
         self.set_station_mode()?;
         self.connect_access_point(ssid, key)?;
         self.process_urc_messages();
@@ -197,25 +471,63 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
     }
 }
 
-impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usize, const RX_SIZE: usize>
-    Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE>
+impl<
+        A: AtatClient,
+        T: Timer<TIMER_HZ>,
+        const TIMER_HZ: u32,
+        const TX_SIZE: usize,
+        const RX_SIZE: usize,
+        const SOCKETS: usize,
+    > Adapter<A, T, TIMER_HZ, TX_SIZE, RX_SIZE, SOCKETS>
 {
     /// Creates a new network adapter. Client needs to be in timeout or blocking mode
+    ///
+    /// The timer is used for blocking waits by `start()` and `wait()` as well as for measuring send timeouts by
+    /// `now()`. So `now()` needs to be free-running and monotonic, i.e. it must not be reset by `start()`.
     pub fn new(client: A, timer: T) -> Self {
         Self {
             client,
             timer,
             send_timeout: 5_000.millis(),
             joined: false,
+            join_error_code: None,
+            join_pending: false,
+            join_response: None,
             ip_assigned: false,
+            restarted: false,
+            station_enabled: false,
+            soft_ap_enabled: false,
+            station_events: Deque::new(),
             multi_connections_enabled: false,
-            passive_mode_enabled: false,
-            sockets: [SocketState::Closed; 5],
-            data_available: [0; 5],
+            passthrough_active: false,
+            single_connected: false,
+            receiving_mode_configured: false,
+            #[cfg(feature = "active-mode")]
+            active_mode: false,
+            #[cfg(feature = "active-mode")]
+            buffers: core::array::from_fn(|_| Deque::new()),
+            #[cfg(feature = "active-mode")]
+            buffer_overflow: [false; SOCKETS],
+            data_info_enabled: false,
+            server_port: None,
+            server_max_connections: SOCKETS,
+            sockets: [SocketState::Closed; SOCKETS],
+            connecting: [false; SOCKETS],
+            data_available: [0; SOCKETS],
+            receive_poll_deadlines: [None; SOCKETS],
+            receive_length_polling: None,
+            unsupported_link_id: None,
+            remotes: [None; SOCKETS],
+            socket_options: [None; SOCKETS],
+            capabilities: Capabilities::default(),
+            long_transmission: false,
+            send_progress: None,
+            pending_send: None,
             recv_byte_count: None,
             send_confirmed: None,
             already_connected: false,
             data: None,
+            data_remote: None,
         }
     }
 
@@ -226,11 +538,28 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
         }
 
         // Avoid full response queue, which gets full for a unknown reason
-        let _ = self.client.check_response(&SetSocketReceivingModeCommand::passive_mode());
+        let result = self.client.check_response(&SetSocketReceivingModeCommand::passive_mode());
+
+        // Final response of the unconfirmed CWJAP command. As no command is awaiting a response, ATAT signals
+        // OK as invalid response.
+        if self.join_pending && self.join_response.is_none() {
+            match result {
+                Err(nb::Error::Other(AtError::InvalidResponse)) => self.join_response = Some(Ok(())),
+                Err(nb::Error::Other(AtError::Timeout)) | Err(nb::Error::WouldBlock) | Ok(_) => {}
+                Err(nb::Error::Other(error)) => self.join_response = Some(Err(error)),
+            }
+        }
     }
 
     /// Handles a single URC message
     pub(crate) fn handle_urc(&mut self, message: URCMessages<RX_SIZE>) {
+        if let Some(link_id) = message.link_id() {
+            if link_id >= SOCKETS {
+                self.unsupported_link_id = Some(link_id);
+                return;
+            }
+        }
+
         match message {
             URCMessages::WifiDisconnected => {
                 self.joined = false;
@@ -238,34 +567,96 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
             }
             URCMessages::ReceivedIP => self.ip_assigned = true,
             URCMessages::WifiConnected => self.joined = true,
-            URCMessages::Ready => {}
-            URCMessages::SocketConnected(link_id) => self.sockets[link_id] = SocketState::Connected,
-            URCMessages::SocketClosed(link_id) => self.sockets[link_id] = SocketState::Closing,
+            URCMessages::JoinFailed(code) => self.join_error_code = Some(code),
+            URCMessages::Ready => self.handle_restart(),
+            URCMessages::SocketConnected(link_id) => {
+                // Connection of a remote client to the local server
+                if self.server_port.is_some() && self.sockets[link_id] == SocketState::Closed {
+                    self.sockets[link_id] = SocketState::Incoming;
+                    self.reset_received_data(link_id);
+                    return;
+                }
+
+                self.sockets[link_id] = SocketState::Connected;
+                self.connecting[link_id] = false;
+            }
+            URCMessages::SocketClosed(link_id) => {
+                // Client connection was closed before getting accepted
+                if self.sockets[link_id] == SocketState::Incoming {
+                    self.sockets[link_id] = SocketState::Closed;
+                    return;
+                }
+
+                self.sockets[link_id] = SocketState::Closing
+            }
+            URCMessages::SingleConnected => self.single_connected = true,
+            URCMessages::SingleClosed => self.single_connected = false,
             URCMessages::AlreadyConnected => self.already_connected = true,
             URCMessages::ReceivedBytes(count) => self.recv_byte_count = Some(count),
+            URCMessages::SendProgress(sent, _) => self.send_progress = Some(sent),
             URCMessages::SendConfirmation => self.send_confirmed = Some(true),
             URCMessages::SendFail => self.send_confirmed = Some(false),
-            URCMessages::DataAvailable(link_id, length) => {
-                if link_id < self.sockets.len() {
-                    self.data_available[link_id] = length;
-                }
+            URCMessages::DataAvailable(link_id, length) => self.data_available[link_id] = length,
+            URCMessages::RemoteDataAvailable(link_id, length, remote) => {
+                self.data_available[link_id] = length;
+                self.remotes[link_id] = Some(remote);
+            }
+            #[cfg(feature = "active-mode")]
+            URCMessages::ActiveData(link_id, data) => self.buffer_data(link_id, &data),
+            #[cfg(feature = "active-mode")]
+            URCMessages::ActiveRemoteData(link_id, remote, data) => {
+                self.remotes[link_id] = Some(remote);
+                self.buffer_data(link_id, &data);
             }
+            #[cfg(not(feature = "active-mode"))]
+            URCMessages::ActiveData(..) | URCMessages::ActiveRemoteData(..) => {}
             URCMessages::Data(data) => self.data = Some(data),
+            URCMessages::RemoteData(remote, data) => {
+                self.data = Some(data);
+                self.data_remote = Some(remote);
+            }
+            URCMessages::StationConnected(mac) => self.push_station_event(StationEvent::Connected(mac)),
+            URCMessages::StationDisconnected(mac) => self.push_station_event(StationEvent::Disconnected(mac)),
+            URCMessages::StationIpAssigned(mac, ip) => self.push_station_event(StationEvent::IpAssigned(mac, ip)),
             URCMessages::Echo => {}
             URCMessages::Unknown => {}
         }
     }
 
-    /// Sends the command for switching to station mode
+    /// Sends the command for switching to station mode. SoftAP+Station mode is used if SoftAP is running.
     fn set_station_mode(&mut self) -> Result<(), JoinError> {
-        let command = WifiModeCommand::station_mode();
+        let command = match self.soft_ap_enabled {
+            true => WifiModeCommand::station_soft_ap_mode(),
+            false => WifiModeCommand::station_mode(),
+        };
         self.send_command(command)?;
+        self.station_enabled = true;
 
         Ok(())
     }
 
     /// Sends the command for setting the WIFI credentials
     fn connect_access_point(&mut self, ssid: &str, key: &str) -> Result<(), JoinError> {
+        let command = Self::access_point_command(ssid, key)?;
+        self.join_error_code = None;
+
+        // ESP-AT signals the failure reason by +CWJAP message before the command error
+        match self.send_command(command) {
+            Ok(_) => Ok(()),
+            Err(JoinError::ConnectError(error)) => {
+                self.process_urc_messages();
+
+                match self.join_error_code.take() {
+                    None => Err(JoinError::ConnectError(error)),
+                    Some(code) => Err(JoinError::from_code(code, error)),
+                }
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Validates the WIFI credentials and creates the CWJAP command
+    fn access_point_command(ssid: &str, key: &str) -> Result<AccessPointConnectCommand, JoinError> {
         if ssid.len() > 32 {
             return Err(JoinError::InvalidSSDLength);
         }
@@ -274,16 +665,30 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
             return Err(JoinError::InvalidPasswordLength);
         }
 
-        let command = AccessPointConnectCommand::new(ssid.into(), key.into());
-        self.send_command(command)?;
-
-        Ok(())
+        Ok(AccessPointConnectCommand::new(ssid.into(), key.into()))
     }
 
-    /// Sends a command and maps the error if the command failed
+    /// Sends a command and maps the error if the command failed.
+    /// Commands are rejected while passthrough mode is active or a socket transmission is unconfirmed.
     pub(crate) fn send_command<Cmd: AtatCmd<LEN> + CommandErrorHandler, const LEN: usize>(
         &mut self,
         command: Cmd,
+    ) -> Result<Cmd::Response, Cmd::Error> {
+        if self.passthrough_active {
+            return Err(Cmd::PASSTHROUGH_ERROR);
+        }
+
+        if self.pending_send.is_some() {
+            return Err(Cmd::SEND_PENDING_ERROR);
+        }
+
+        self.send_raw(command)
+    }
+
+    /// Sends the command regardless of passthrough mode, e.g. for transmitting passthrough data
+    pub(crate) fn send_raw<Cmd: AtatCmd<LEN> + CommandErrorHandler, const LEN: usize>(
+        &mut self,
+        command: Cmd,
     ) -> Result<Cmd::Response, Cmd::Error> {
         let result = self.client.send(&command);
         if let nb::Result::Err(error) = result {
@@ -296,10 +701,141 @@ impl<A: AtatClient, T: Timer<TIMER_HZ>, const TIMER_HZ: u32, const TX_SIZE: usiz
         Ok(result.unwrap())
     }
 
+    /// Sets a static IPv4 address, gateway and netmask of the station interface. DHCP gets disabled.
+    pub fn set_static_ip(&mut self, config: &IpConfig) -> Result<(), IpConfigError> {
+        self.send_command(SetStaticIpCommand::new(config))?;
+        Ok(())
+    }
+
+    /// Enables or disables DHCP of the station interface
+    pub fn set_dhcp(&mut self, enabled: bool) -> Result<(), IpConfigError> {
+        self.send_command(SetDhcpCommand::station(enabled))?;
+        Ok(())
+    }
+
+    /// Returns the current IPv4 address, gateway and netmask of the station interface
+    pub fn get_ip_config(&mut self) -> Result<IpConfig, IpConfigError> {
+        self.send_command(IpConfigQueryCommand::new())
+    }
+
+    /// Starts connecting to an WIFI access point. The CWJAP command is sent without waiting for the response,
+    /// so the result needs to be polled by `poll_join()`.
+    pub fn start_join(&mut self, ssid: &str, key: &str) -> Result<(), JoinError> {
+        let command = Self::access_point_command(ssid, key)?;
+        self.set_station_mode()?;
+
+        // Discard the state of previous connections
+        self.process_urc_messages();
+        self.joined = false;
+        self.ip_assigned = false;
+        self.join_error_code = None;
+
+        self.send_command(UnconfirmedCommand::new(command))?;
+        self.join_pending = true;
+        self.join_response = None;
+
+        Ok(())
+    }
+
+    /// Returns [nb::Error::WouldBlock] until an IP was assigned or ESP-AT signaled a failure.
+    /// Timeouts are handled by ESP-AT and returned as [JoinError::Timeout].
+    ///
+    /// The join is just completed once the final response of CWJAP command was received, so that it's not taken
+    /// as response of the next command. Therefore no other commands should be sent until the join is completed.
+    pub fn poll_join(&mut self) -> nb::Result<JoinState, JoinError> {
+        if !self.join_pending {
+            return nb::Result::Err(nb::Error::Other(JoinError::NoJoinInProgress));
+        }
+
+        self.process_urc_messages();
+
+        let response = match self.join_response.clone() {
+            None => return nb::Result::Err(nb::Error::WouldBlock),
+            Some(response) => response,
+        };
+
+        if let Some(code) = self.join_error_code.take() {
+            self.join_pending = false;
+            return nb::Result::Err(nb::Error::Other(JoinError::from_code(code, AtError::Error)));
+        }
+
+        if let Err(error) = response {
+            self.join_pending = false;
+            return nb::Result::Err(nb::Error::Other(JoinError::ConnectError(error)));
+        }
+
+        if !self.ip_assigned {
+            return nb::Result::Err(nb::Error::WouldBlock);
+        }
+
+        self.join_pending = false;
+        nb::Result::Ok(JoinState {
+            connected: self.joined,
+            ip_assigned: self.ip_assigned,
+        })
+    }
+
+    /// Disconnects from the WIFI access point and stops automatic reconnecting
+    ///
+    /// ESP-AT drops all connections, so connected sockets are marked as closing and need to be closed by
+    /// calling `close()`.
+    pub fn leave(&mut self) -> Result<(), LeaveError> {
+        self.send_command(AccessPointDisconnectCommand::new())?;
+        self.process_urc_messages();
+
+        self.join_pending = false;
+        self.joined = false;
+        self.ip_assigned = false;
+
+        for state in self.sockets.iter_mut() {
+            match state {
+                SocketState::Connected => *state = SocketState::Closing,
+                SocketState::Incoming => *state = SocketState::Closed,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the available access points (max. 16) sorted by signal strength
+    pub fn scan(&mut self) -> Result<Vec<AccessPoint, 16>, ScanError> {
+        self.send_command(AccessPointListOptionsCommand::new())?;
+        let responses = self.send_command(ListAccessPointsCommand::new())?;
+
+        Ok(responses.into_iter().map(AccessPoint::from).collect())
+    }
+
     /// Sets the timeout for sending TCP data in ms
     pub fn set_send_timeout_ms(&mut self, timeout: u32) {
         self.send_timeout = TimerDurationU32::millis(timeout);
     }
+
+    /// Enables or disables the long transmission mode for sending TCP data. Default: disabled
+    ///
+    /// If enabled, `send()` transmits the whole buffer by a single CIPSENDL command. Data is still written in
+    /// TX_SIZE blocks, but just the complete transmission gets confirmed by ESP-AT. This avoids the handshake
+    /// per block, e.g. for bulk uploads. Requires a firmware version supporting CIPSENDL command, otherwise
+    /// data is sent in blocks if the firmware was probed by `firmware_info()`.
+    pub fn set_long_transmission(&mut self, enabled: bool) {
+        self.long_transmission = enabled;
+    }
+
+    /// Enables polling the length of received data (CIPRECVLEN) as fallback for missed +IPD messages.
+    /// Default: disabled
+    ///
+    /// Once `receive()` of a socket returned no data for the given interval in ms, the receive lengths of all
+    /// links are queried by the next call. The interval is measured by `now()` of the timer. Polling is skipped
+    /// if the firmware was probed by `firmware_info()` and lacks CIPRECVLEN support.
+    pub fn set_receive_length_polling(&mut self, interval_ms: Option<u32>) {
+        self.receive_length_polling = interval_ms.map(TimerDurationU32::millis);
+    }
+
+    /// Sets the max. number of clients allowed to connect to the TCP server. Default: SOCKETS
+    /// Gets applied when calling `listen()`.
+    pub fn set_server_max_connections(&mut self, count: usize) {
+        self.server_max_connections = count;
+    }
 }
 
 /// Local IP and MAC addresses
@@ -353,3 +889,181 @@ impl LocalAddress {
         Ok(data)
     }
 }
+
+/// IPv4 configuration of the station interface
+#[derive(Clone, Debug, PartialEq)]
+pub struct IpConfig {
+    /// IPv4 address
+    pub ip: Ipv4Addr,
+
+    /// Gateway address
+    pub gateway: Ipv4Addr,
+
+    /// Netmask
+    pub netmask: Ipv4Addr,
+}
+
+impl AtatResp for IpConfig {}
+
+impl IpConfig {
+    /// Parses the response of CIPSTA query, e.g. `+CIPSTA:ip:"10.0.0.181"`, one line per address
+    pub(crate) fn from_response(response: &[u8]) -> Result<Self, AtError> {
+        let response = core::str::from_utf8(response).map_err(|_| AtError::Parse)?;
+        let mut config = Self {
+            ip: Ipv4Addr::UNSPECIFIED,
+            gateway: Ipv4Addr::UNSPECIFIED,
+            netmask: Ipv4Addr::UNSPECIFIED,
+        };
+
+        for line in response.lines() {
+            let line = match line.trim().strip_prefix("+CIPSTA:") {
+                None => continue,
+                Some(line) => line,
+            };
+
+            let (address_type, address) = line.split_once(':').ok_or(AtError::Parse)?;
+            let address = address.trim_matches('"');
+
+            match address_type {
+                "ip" => config.ip = Ipv4Addr::from_str(address).map_err(|_| AtError::Parse)?,
+                "gateway" => config.gateway = Ipv4Addr::from_str(address).map_err(|_| AtError::Parse)?,
+                "netmask" => config.netmask = Ipv4Addr::from_str(address).map_err(|_| AtError::Parse)?,
+                _ => {}
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Encryption type of an access point
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Encryption {
+    /// No encryption
+    Open,
+    /// WEP
+    Wep,
+    /// WPA PSK
+    WpaPsk,
+    /// WPA2 PSK
+    Wpa2Psk,
+    /// WPA/WPA2 PSK
+    WpaWpa2Psk,
+    /// WPA2 Enterprise
+    Wpa2Enterprise,
+    /// WPA3 PSK
+    Wpa3Psk,
+    /// WPA2/WPA3 PSK
+    Wpa2Wpa3Psk,
+    /// WAPI PSK
+    WapiPsk,
+    /// Opportunistic wireless encryption
+    Owe,
+    /// Encryption type unknown to this crate
+    Unknown(u8),
+}
+
+impl From<u8> for Encryption {
+    fn from(ecn: u8) -> Self {
+        match ecn {
+            0 => Encryption::Open,
+            1 => Encryption::Wep,
+            2 => Encryption::WpaPsk,
+            3 => Encryption::Wpa2Psk,
+            4 => Encryption::WpaWpa2Psk,
+            5 => Encryption::Wpa2Enterprise,
+            6 => Encryption::Wpa3Psk,
+            7 => Encryption::Wpa2Wpa3Psk,
+            8 => Encryption::WapiPsk,
+            9 => Encryption::Owe,
+            _ => Encryption::Unknown(ecn),
+        }
+    }
+}
+
+/// Access point found by scanning
+#[derive(Clone, Debug)]
+pub struct AccessPoint {
+    /// SSID of the access point
+    pub ssid: String<32>,
+
+    /// Signal strength in dBm
+    pub rssi: i8,
+
+    /// WIFI channel
+    pub channel: u8,
+
+    /// Encryption type
+    pub encryption: Encryption,
+
+    /// MAC address of the access point
+    pub bssid: String<17>,
+}
+
+impl From<AccessPointResponse> for AccessPoint {
+    fn from(response: AccessPointResponse) -> Self {
+        Self {
+            ssid: String::from(response.ssid.as_str()),
+            rssi: response.rssi,
+            channel: response.channel,
+            encryption: Encryption::from(response.ecn),
+            bssid: String::from(response.mac.as_str()),
+        }
+    }
+}
+
+/// Active DNS servers
+#[derive(Default, Clone, Debug)]
+pub struct DnsServers {
+    /// True if custom DNS servers are set, false if servers are provided by DHCP
+    pub custom: bool,
+
+    /// Addresses of the active DNS servers (max. 3)
+    pub servers: Vec<IpAddr, 3>,
+}
+
+impl AtatResp for DnsServers {}
+
+impl DnsServers {
+    /// Parses the response of CIPDNS query, e.g. `+CIPDNS:1,"208.67.222.222","8.8.8.8"`
+    pub(crate) fn from_response(response: &[u8]) -> Result<Self, AtError> {
+        let response = core::str::from_utf8(response).map_err(|_| AtError::Parse)?;
+        let response = response.trim().strip_prefix("+CIPDNS:").ok_or(AtError::Parse)?;
+
+        let mut parts = response.split(',');
+        let mut data = Self {
+            custom: parts.next() == Some("1"),
+            servers: Vec::new(),
+        };
+
+        for part in parts {
+            let address = IpAddr::from_str(part.trim().trim_matches('"')).map_err(|_| AtError::Parse)?;
+            data.servers.push(address).map_err(|_| AtError::Parse)?;
+        }
+
+        Ok(data)
+    }
+
+    /// Parses the response of CIPDNS_CUR query (ESP-AT v1.x), e.g. `+CIPDNS_CUR:208.67.222.222`, one line per server.
+    /// As the response does not state if custom servers are set, `custom` is always false.
+    pub(crate) fn from_legacy_response(response: &[u8]) -> Result<Self, AtError> {
+        let response = core::str::from_utf8(response).map_err(|_| AtError::Parse)?;
+        let mut data = Self::default();
+
+        for line in response.lines() {
+            let line = match line.trim().strip_prefix("+CIPDNS_CUR:") {
+                None => continue,
+                Some(line) => line,
+            };
+
+            let address = IpAddr::from_str(line.trim().trim_matches('"')).map_err(|_| AtError::Parse)?;
+            data.servers.push(address).map_err(|_| AtError::Parse)?;
+        }
+
+        if data.servers.is_empty() {
+            return Err(AtError::Parse);
+        }
+
+        Ok(data)
+    }
+}